@@ -0,0 +1,180 @@
+use crate::hashset::{Hashset, TwolevelHashset};
+use crate::traits::Key;
+use arrayvec::ArrayVec;
+use std::alloc::Allocator;
+use std::intrinsics::unlikely;
+
+const TWOLEVEL_THRESHOLD: usize = 1 << 16;
+
+enum Stage<K, const N: usize, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    Array(ArrayVec<K, N>),
+    Hashset(Hashset<K, A>),
+    Twolevel(Box<TwolevelHashset<K, A>, A>),
+}
+
+/// A set for exact distinct counting, which starts as an inline array, and is converted
+/// to a `Hashset` if it has more than `N` keys and then to a `TwolevelHashset`
+/// if it's large enough.
+pub struct AdaptiveHashset<K, const N: usize = 8, A = crate::allocator::Default>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    allocator: A,
+    stage: Stage<K, N, A>,
+}
+
+impl<K, const N: usize, A> AdaptiveHashset<K, N, A>
+where
+    K: Key,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+}
+
+impl<K, const N: usize, A> AdaptiveHashset<K, N, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            allocator,
+            stage: Stage::Array(ArrayVec::new()),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        match &self.stage {
+            Stage::Array(x) => x.len(),
+            Stage::Hashset(x) => x.len(),
+            Stage::Twolevel(x) => x.len(),
+        }
+    }
+    pub fn is_array(&self) -> bool {
+        matches!(self.stage, Stage::Array(_))
+    }
+    pub fn is_twolevel(&self) -> bool {
+        matches!(self.stage, Stage::Twolevel(_))
+    }
+    #[inline(always)]
+    pub fn contains(&self, key: &K) -> bool {
+        match &self.stage {
+            Stage::Array(x) => x.contains(key),
+            Stage::Hashset(x) => x.contains(key),
+            Stage::Twolevel(x) => x.contains(key),
+        }
+    }
+    /// Returns `true` if the key is newly inserted.
+    #[inline(always)]
+    pub fn insert(&mut self, key: K) -> bool {
+        match &mut self.stage {
+            Stage::Array(x) => {
+                if x.contains(&key) {
+                    return false;
+                }
+                if x.try_push(key).is_ok() {
+                    return true;
+                }
+                self.convert_to_hashset();
+                self.insert(key)
+            }
+            Stage::Hashset(x) => {
+                let inserted = x.insert(key);
+                if unlikely(inserted && x.len() > TWOLEVEL_THRESHOLD) {
+                    self.convert_to_twolevel();
+                }
+                inserted
+            }
+            Stage::Twolevel(x) => x.insert(key),
+        }
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        let (array, hashset, twolevel) = match &self.stage {
+            Stage::Array(x) => (Some(x), None, None),
+            Stage::Hashset(x) => (None, Some(x), None),
+            Stage::Twolevel(x) => (None, None, Some(x)),
+        };
+        array
+            .into_iter()
+            .flat_map(|x| x.iter())
+            .chain(hashset.into_iter().flat_map(|x| x.iter()))
+            .chain(twolevel.into_iter().flat_map(|x| x.iter()))
+    }
+    /// Moves all keys of `other` into `self`, whatever stages they are in.
+    pub fn merge(&mut self, other: Self) {
+        match other.stage {
+            Stage::Array(keys) => {
+                for key in keys {
+                    self.insert(key);
+                }
+            }
+            Stage::Hashset(set) => {
+                if let Stage::Array(_) = self.stage {
+                    self.convert_to_hashset();
+                }
+                match &mut self.stage {
+                    Stage::Array(_) => unreachable!(),
+                    Stage::Hashset(x) => {
+                        x.union(set);
+                        if x.len() > TWOLEVEL_THRESHOLD {
+                            self.convert_to_twolevel();
+                        }
+                    }
+                    Stage::Twolevel(x) => {
+                        for key in set {
+                            x.insert(key);
+                        }
+                    }
+                }
+            }
+            Stage::Twolevel(set) => {
+                self.convert_to_twolevel();
+                let Stage::Twolevel(x) = &mut self.stage else {
+                    unreachable!()
+                };
+                x.union(*set);
+            }
+        }
+    }
+    fn convert_to_hashset(&mut self) {
+        let Stage::Array(keys) = &self.stage else {
+            return;
+        };
+        let mut set = Hashset::with_capacity_in(N * 2, self.allocator.clone());
+        for &key in keys.iter() {
+            set.insert(key);
+        }
+        self.stage = Stage::Hashset(set);
+    }
+    fn convert_to_twolevel(&mut self) {
+        if let Stage::Twolevel(_) = self.stage {
+            return;
+        }
+        let mut set = TwolevelHashset::new_in(self.allocator.clone());
+        match std::mem::replace(&mut self.stage, Stage::Array(ArrayVec::new())) {
+            Stage::Array(keys) => {
+                for key in keys {
+                    set.insert(key);
+                }
+            }
+            Stage::Hashset(keys) => {
+                for key in keys {
+                    set.insert(key);
+                }
+            }
+            Stage::Twolevel(_) => unreachable!(),
+        }
+        self.stage = Stage::Twolevel(Box::new_in(set, self.allocator.clone()));
+    }
+}
@@ -0,0 +1,432 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+/// Values accepted by aggregate functions.
+pub trait Scalar: PartialOrd {
+    type Owned: Borrow<Self>;
+    fn own(&self) -> Self::Owned;
+    fn serialize(&self, buf: &mut Vec<u8>);
+    /// # Panics
+    ///
+    /// Panics if `buf` is truncated.
+    fn deserialize(buf: &mut &[u8]) -> Self::Owned;
+}
+
+/// Numeric values, which are summed up in `Sum`.
+pub trait Number: Scalar<Owned = Self> + Copy + Default {
+    type Sum: Number<Sum = Self::Sum>;
+    fn widen(self) -> Self::Sum;
+    /// Integers wrap on overflow.
+    fn add(self, other: Self) -> Self;
+    fn as_f64(self) -> f64;
+}
+
+fn take<'b>(buf: &mut &'b [u8], n: usize) -> &'b [u8] {
+    let (x, rest) = buf.split_at(n);
+    *buf = rest;
+    x
+}
+
+macro_rules! impl_scalar_number {
+    ($t: ty) => {
+        impl Scalar for $t {
+            type Owned = $t;
+            #[inline(always)]
+            fn own(&self) -> $t {
+                *self
+            }
+            fn serialize(&self, buf: &mut Vec<u8>) {
+                buf.extend_from_slice(&self.to_le_bytes());
+            }
+            fn deserialize(buf: &mut &[u8]) -> $t {
+                let x = take(buf, std::mem::size_of::<$t>());
+                <$t>::from_le_bytes(x.try_into().unwrap())
+            }
+        }
+    };
+}
+
+macro_rules! impl_integer {
+    ($t: ty, $sum: ty) => {
+        impl_scalar_number!($t);
+        impl Number for $t {
+            type Sum = $sum;
+            #[inline(always)]
+            fn widen(self) -> $sum {
+                self as $sum
+            }
+            #[inline(always)]
+            fn add(self, other: Self) -> Self {
+                self.wrapping_add(other)
+            }
+            #[inline(always)]
+            fn as_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    };
+}
+
+macro_rules! impl_float {
+    ($t: ty) => {
+        impl_scalar_number!($t);
+        impl Number for $t {
+            type Sum = f64;
+            #[inline(always)]
+            fn widen(self) -> f64 {
+                self as f64
+            }
+            #[inline(always)]
+            fn add(self, other: Self) -> Self {
+                self + other
+            }
+            #[inline(always)]
+            fn as_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    };
+}
+
+impl_integer!(u8, u64);
+impl_integer!(u16, u64);
+impl_integer!(u32, u64);
+impl_integer!(u64, u64);
+impl_integer!(i8, i64);
+impl_integer!(i16, i64);
+impl_integer!(i32, i64);
+impl_integer!(i64, i64);
+impl_float!(f32);
+impl_float!(f64);
+
+impl Scalar for [u8] {
+    type Owned = Vec<u8>;
+    fn own(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+    fn serialize(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&(self.len() as u64).to_le_bytes());
+        buf.extend_from_slice(self);
+    }
+    fn deserialize(buf: &mut &[u8]) -> Vec<u8> {
+        let len = u64::deserialize(buf) as usize;
+        take(buf, len).to_vec()
+    }
+}
+
+impl Scalar for str {
+    type Owned = String;
+    fn own(&self) -> String {
+        self.to_string()
+    }
+    fn serialize(&self, buf: &mut Vec<u8>) {
+        self.as_bytes().serialize(buf);
+    }
+    fn deserialize(buf: &mut &[u8]) -> String {
+        String::from_utf8(<[u8]>::deserialize(buf)).unwrap()
+    }
+}
+
+fn serialize_option<T: Scalar + ?Sized>(state: &Option<T::Owned>, buf: &mut Vec<u8>) {
+    match state {
+        None => buf.push(0),
+        Some(x) => {
+            buf.push(1);
+            x.borrow().serialize(buf);
+        }
+    }
+}
+
+fn deserialize_option<T: Scalar + ?Sized>(buf: &mut &[u8]) -> Option<T::Owned> {
+    match take(buf, 1)[0] {
+        0 => None,
+        _ => Some(T::deserialize(buf)),
+    }
+}
+
+/// An aggregate function, whose arguments live for `'a`.
+pub trait AggregateFunction<'a> {
+    type Arg;
+    type State;
+    type Output;
+    fn init(&self) -> Self::State;
+    fn update(&self, state: &mut Self::State, arg: Self::Arg);
+    fn update_batch<I>(&self, state: &mut Self::State, args: I)
+    where
+        I: IntoIterator<Item = Self::Arg>,
+    {
+        for arg in args {
+            self.update(state, arg);
+        }
+    }
+    fn merge(&self, state: &mut Self::State, other: Self::State);
+    fn finalize(&self, state: Self::State) -> Self::Output;
+    fn serialize(&self, state: &Self::State, buf: &mut Vec<u8>);
+    /// # Panics
+    ///
+    /// Panics if `buf` is truncated.
+    fn deserialize(&self, buf: &mut &[u8]) -> Self::State;
+}
+
+macro_rules! function {
+    ($name: ident, $doc: literal) => {
+        #[doc = $doc]
+        pub struct $name<T: ?Sized>(PhantomData<fn(&T)>);
+
+        impl<T: ?Sized> $name<T> {
+            pub const fn new() -> Self {
+                Self(PhantomData)
+            }
+        }
+
+        impl<T: ?Sized> Default for $name<T> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+function!(Count, "Counts arguments.");
+function!(
+    Sum,
+    "Sums up arguments, whose integers are widened to 64 bits."
+);
+function!(Min, "Returns the minimum argument.");
+function!(Max, "Returns the maximum argument.");
+function!(Avg, "Returns the mean of arguments.");
+function!(Any, "Returns the first argument.");
+
+impl<'a, T: ?Sized + 'a> AggregateFunction<'a> for Count<T> {
+    type Arg = &'a T;
+    type State = u64;
+    type Output = u64;
+    #[inline(always)]
+    fn init(&self) -> u64 {
+        0
+    }
+    #[inline(always)]
+    fn update(&self, state: &mut u64, _: &'a T) {
+        *state += 1;
+    }
+    fn merge(&self, state: &mut u64, other: u64) {
+        *state += other;
+    }
+    fn finalize(&self, state: u64) -> u64 {
+        state
+    }
+    fn serialize(&self, state: &u64, buf: &mut Vec<u8>) {
+        state.serialize(buf);
+    }
+    fn deserialize(&self, buf: &mut &[u8]) -> u64 {
+        u64::deserialize(buf)
+    }
+}
+
+impl<'a, T: Number + 'a> AggregateFunction<'a> for Sum<T> {
+    type Arg = &'a T;
+    type State = T::Sum;
+    type Output = T::Sum;
+    #[inline(always)]
+    fn init(&self) -> T::Sum {
+        Default::default()
+    }
+    #[inline(always)]
+    fn update(&self, state: &mut T::Sum, arg: &'a T) {
+        *state = state.add(arg.widen());
+    }
+    fn merge(&self, state: &mut T::Sum, other: T::Sum) {
+        *state = state.add(other);
+    }
+    fn finalize(&self, state: T::Sum) -> T::Sum {
+        state
+    }
+    fn serialize(&self, state: &T::Sum, buf: &mut Vec<u8>) {
+        state.serialize(buf);
+    }
+    fn deserialize(&self, buf: &mut &[u8]) -> T::Sum {
+        T::Sum::deserialize(buf)
+    }
+}
+
+impl<'a, T: Scalar + ?Sized + 'a> AggregateFunction<'a> for Min<T> {
+    type Arg = &'a T;
+    type State = Option<T::Owned>;
+    type Output = Option<T::Owned>;
+    #[inline(always)]
+    fn init(&self) -> Self::State {
+        None
+    }
+    #[inline(always)]
+    fn update(&self, state: &mut Self::State, arg: &'a T) {
+        let replace = match state {
+            None => true,
+            Some(x) => arg < Borrow::<T>::borrow(x),
+        };
+        if replace {
+            *state = Some(arg.own());
+        }
+    }
+    fn merge(&self, state: &mut Self::State, other: Self::State) {
+        if let Some(other) = other {
+            let replace = match state {
+                None => true,
+                Some(x) => other.borrow() < Borrow::<T>::borrow(x),
+            };
+            if replace {
+                *state = Some(other);
+            }
+        }
+    }
+    fn finalize(&self, state: Self::State) -> Self::Output {
+        state
+    }
+    fn serialize(&self, state: &Self::State, buf: &mut Vec<u8>) {
+        serialize_option::<T>(state, buf);
+    }
+    fn deserialize(&self, buf: &mut &[u8]) -> Self::State {
+        deserialize_option::<T>(buf)
+    }
+}
+
+impl<'a, T: Scalar + ?Sized + 'a> AggregateFunction<'a> for Max<T> {
+    type Arg = &'a T;
+    type State = Option<T::Owned>;
+    type Output = Option<T::Owned>;
+    #[inline(always)]
+    fn init(&self) -> Self::State {
+        None
+    }
+    #[inline(always)]
+    fn update(&self, state: &mut Self::State, arg: &'a T) {
+        let replace = match state {
+            None => true,
+            Some(x) => arg > Borrow::<T>::borrow(x),
+        };
+        if replace {
+            *state = Some(arg.own());
+        }
+    }
+    fn merge(&self, state: &mut Self::State, other: Self::State) {
+        if let Some(other) = other {
+            let replace = match state {
+                None => true,
+                Some(x) => other.borrow() > Borrow::<T>::borrow(x),
+            };
+            if replace {
+                *state = Some(other);
+            }
+        }
+    }
+    fn finalize(&self, state: Self::State) -> Self::Output {
+        state
+    }
+    fn serialize(&self, state: &Self::State, buf: &mut Vec<u8>) {
+        serialize_option::<T>(state, buf);
+    }
+    fn deserialize(&self, buf: &mut &[u8]) -> Self::State {
+        deserialize_option::<T>(buf)
+    }
+}
+
+impl<'a, T: Number + 'a> AggregateFunction<'a> for Avg<T> {
+    type Arg = &'a T;
+    type State = (T::Sum, u64);
+    type Output = Option<f64>;
+    #[inline(always)]
+    fn init(&self) -> Self::State {
+        Default::default()
+    }
+    #[inline(always)]
+    fn update(&self, state: &mut Self::State, arg: &'a T) {
+        state.0 = state.0.add(arg.widen());
+        state.1 += 1;
+    }
+    fn merge(&self, state: &mut Self::State, other: Self::State) {
+        state.0 = state.0.add(other.0);
+        state.1 += other.1;
+    }
+    fn finalize(&self, state: Self::State) -> Option<f64> {
+        if state.1 == 0 {
+            None
+        } else {
+            Some(state.0.as_f64() / state.1 as f64)
+        }
+    }
+    fn serialize(&self, state: &Self::State, buf: &mut Vec<u8>) {
+        state.0.serialize(buf);
+        state.1.serialize(buf);
+    }
+    fn deserialize(&self, buf: &mut &[u8]) -> Self::State {
+        let sum = T::Sum::deserialize(buf);
+        let count = u64::deserialize(buf);
+        (sum, count)
+    }
+}
+
+impl<'a, T: Scalar + ?Sized + 'a> AggregateFunction<'a> for Any<T> {
+    type Arg = &'a T;
+    type State = Option<T::Owned>;
+    type Output = Option<T::Owned>;
+    #[inline(always)]
+    fn init(&self) -> Self::State {
+        None
+    }
+    #[inline(always)]
+    fn update(&self, state: &mut Self::State, arg: &'a T) {
+        if state.is_none() {
+            *state = Some(arg.own());
+        }
+    }
+    fn merge(&self, state: &mut Self::State, other: Self::State) {
+        if state.is_none() {
+            *state = other;
+        }
+    }
+    fn finalize(&self, state: Self::State) -> Self::Output {
+        state
+    }
+    fn serialize(&self, state: &Self::State, buf: &mut Vec<u8>) {
+        serialize_option::<T>(state, buf);
+    }
+    fn deserialize(&self, buf: &mut &[u8]) -> Self::State {
+        deserialize_option::<T>(buf)
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($f: ident $i: tt),*) => {
+        /// Several aggregate functions, which are evaluated together.
+        impl<'a, $($f: AggregateFunction<'a>),*> AggregateFunction<'a> for ($($f,)*) {
+            type Arg = ($($f::Arg,)*);
+            type State = ($($f::State,)*);
+            type Output = ($($f::Output,)*);
+            #[inline(always)]
+            fn init(&self) -> Self::State {
+                ($(self.$i.init(),)*)
+            }
+            #[inline(always)]
+            fn update(&self, state: &mut Self::State, arg: Self::Arg) {
+                $(self.$i.update(&mut state.$i, arg.$i);)*
+            }
+            fn merge(&self, state: &mut Self::State, other: Self::State) {
+                $(self.$i.merge(&mut state.$i, other.$i);)*
+            }
+            fn finalize(&self, state: Self::State) -> Self::Output {
+                ($(self.$i.finalize(state.$i),)*)
+            }
+            fn serialize(&self, state: &Self::State, buf: &mut Vec<u8>) {
+                $(self.$i.serialize(&state.$i, buf);)*
+            }
+            fn deserialize(&self, buf: &mut &[u8]) -> Self::State {
+                ($(self.$i.deserialize(buf),)*)
+            }
+        }
+    };
+}
+
+impl_tuple!(F0 0, F1 1);
+impl_tuple!(F0 0, F1 1, F2 2);
+impl_tuple!(F0 0, F1 1, F2 2, F3 3);
+impl_tuple!(F0 0, F1 1, F2 2, F3 3, F4 4);
+impl_tuple!(F0 0, F1 1, F2 2, F3 3, F4 4, F5 5);
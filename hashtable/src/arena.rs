@@ -0,0 +1,138 @@
+use std::alloc::{handle_alloc_error, Allocator, Layout};
+use std::ptr::NonNull;
+
+const MIN_CHUNK: usize = 4 << 10;
+const MAX_CHUNK: usize = 16 << 20;
+
+/// A bump arena, whose chunks are allocated by `A`.
+///
+/// Memory is released only if the arena is dropped.
+pub struct Arena<A: Allocator = crate::allocator::Default> {
+    chunks: Vec<(NonNull<u8>, Layout), A>,
+    start: usize,
+    ptr: usize,
+    end: usize,
+    next: usize,
+    allocated: usize,
+    wasted: usize,
+    adopted: Vec<Arena<A>, A>,
+    allocator: A,
+}
+
+unsafe impl<A: Allocator + Send> Send for Arena<A> {}
+
+unsafe impl<A: Allocator + Sync> Sync for Arena<A> {}
+
+impl<A: Allocator + Clone + Default> Arena<A> {
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+}
+
+impl<A: Allocator + Clone + Default> Default for Arena<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Allocator + Clone> Arena<A> {
+    /// No memory is allocated until the first allocation.
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            chunks: Vec::new_in(allocator.clone()),
+            start: 0,
+            ptr: 0,
+            end: 0,
+            next: MIN_CHUNK,
+            allocated: 0,
+            wasted: 0,
+            adopted: Vec::new_in(allocator.clone()),
+            allocator,
+        }
+    }
+    /// Returns the total size of all chunks, including chunks of adopted arenas.
+    #[inline(always)]
+    pub fn allocated_bytes(&self) -> usize {
+        self.allocated
+    }
+    /// Returns the total size of deallocated memory that can't be reused.
+    #[inline(always)]
+    pub fn wasted_bytes(&self) -> usize {
+        self.wasted
+    }
+    /// Takes over all chunks of `other`, which are released only if `self` is dropped.
+    pub fn adopt(&mut self, other: Self) {
+        if other.allocated != 0 {
+            self.allocated += other.allocated;
+            self.wasted += other.wasted;
+            self.adopted.push(other);
+        }
+    }
+    #[inline(always)]
+    pub fn allocator(&self) -> &A {
+        &self.allocator
+    }
+    /// Zero-sized allocations return dangling pointers.
+    #[inline(always)]
+    pub fn alloc_layout(&mut self, layout: Layout) -> NonNull<u8> {
+        if layout.size() == 0 {
+            return unsafe { NonNull::new_unchecked(layout.align() as *mut u8) };
+        }
+        let start = (self.ptr + layout.align() - 1) & !(layout.align() - 1);
+        if start <= self.end && self.end - start >= layout.size() {
+            self.ptr = start + layout.size();
+            unsafe { NonNull::new_unchecked(start as *mut u8) }
+        } else {
+            self.alloc_layout_slow(layout)
+        }
+    }
+    #[cold]
+    fn alloc_layout_slow(&mut self, layout: Layout) -> NonNull<u8> {
+        let size = std::cmp::max(self.next, layout.size() + layout.align() - 1);
+        let align = std::cmp::max(layout.align(), std::mem::align_of::<u64>());
+        let chunk = Layout::from_size_align(size, align).unwrap();
+        let ptr = match self.allocator.allocate(chunk) {
+            Ok(ptr) => ptr.cast::<u8>(),
+            Err(_) => handle_alloc_error(chunk),
+        };
+        self.chunks.push((ptr, chunk));
+        self.allocated += size;
+        self.next = std::cmp::min(self.next * 2, MAX_CHUNK);
+        self.start = ptr.as_ptr() as usize;
+        self.ptr = ptr.as_ptr() as usize + layout.size();
+        self.end = ptr.as_ptr() as usize + size;
+        ptr
+    }
+    /// Copies `src` into the arena.
+    #[inline(always)]
+    pub fn alloc_slice_copy(&mut self, src: &[u8]) -> &mut [u8] {
+        let ptr = self.alloc_layout(Layout::for_value(src));
+        unsafe {
+            std::ptr::copy_nonoverlapping(src.as_ptr(), ptr.as_ptr(), src.len());
+            std::slice::from_raw_parts_mut(ptr.as_ptr(), src.len())
+        }
+    }
+    /// Deallocates `slice`. Its memory is reused only if it's the last allocation.
+    ///
+    /// # Safety
+    ///
+    /// `slice` is allocated by `alloc_slice_copy` of this arena, and it's never used again.
+    pub unsafe fn dealloc_slice(&mut self, slice: &[u8]) {
+        let start = slice.as_ptr() as usize;
+        if start >= self.start && start + slice.len() == self.ptr {
+            self.ptr = start;
+        } else {
+            self.wasted += slice.len();
+        }
+    }
+}
+
+impl<A: Allocator> Drop for Arena<A> {
+    fn drop(&mut self) {
+        for &(ptr, layout) in self.chunks.iter() {
+            unsafe {
+                self.allocator.deallocate(ptr, layout);
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use core_simd::simd::*;
+use std::alloc::Allocator;
+
+// Salts of split block Bloom filters, see https://github.com/apache/parquet-format/blob/master/BloomFilter.md.
+const SALTS: [u32; 8] = [
+    0x47b6137b, 0x44974d91, 0x8824ad5b, 0xa2b7289d, 0x705495c7, 0x2df1424b, 0x9efc4947, 0x5c6bfb31,
+];
+
+#[repr(C, align(32))]
+#[derive(Clone, Copy)]
+struct Block([u32; 8]);
+
+/// A blocked Bloom filter over key hashes.
+///
+/// The high 32 bits of a hash select a 256-bit block and the low 32 bits set one bit
+/// in every 32-bit word of the block.
+pub struct BloomFilter<A = crate::allocator::Default>
+where
+    A: Allocator,
+{
+    blocks: Box<[Block], A>,
+}
+
+impl<A: Allocator + Default> BloomFilter<A> {
+    pub fn with_capacity(keys: usize, bits_per_key: usize) -> Self {
+        Self::with_capacity_in(keys, bits_per_key, Default::default())
+    }
+}
+
+impl<A: Allocator> BloomFilter<A> {
+    pub fn with_capacity_in(keys: usize, bits_per_key: usize, allocator: A) -> Self {
+        let bits = keys.saturating_mul(bits_per_key);
+        let blocks = std::cmp::max(1, (bits + 255) / 256).next_power_of_two();
+        Self {
+            blocks: unsafe { Box::new_zeroed_slice_in(blocks, allocator).assume_init() },
+        }
+    }
+    /// Returns the size of the filter in bits.
+    #[inline(always)]
+    pub fn bits(&self) -> usize {
+        self.blocks.len() * 256
+    }
+    #[inline(always)]
+    fn block(&self, hash: u64) -> usize {
+        (hash >> 32) as usize & (self.blocks.len() - 1)
+    }
+    #[inline(always)]
+    pub fn insert(&mut self, hash: u64) {
+        let index = self.block(hash);
+        let block = &mut self.blocks[index].0;
+        for i in 0..8 {
+            block[i] |= 1 << ((hash as u32).wrapping_mul(SALTS[i]) >> 27);
+        }
+    }
+    #[inline(always)]
+    pub fn may_contain(&self, hash: u64) -> bool {
+        let block = &self.blocks[self.block(hash)].0;
+        for i in 0..8 {
+            let bit = 1 << ((hash as u32).wrapping_mul(SALTS[i]) >> 27);
+            if block[i] & bit == 0 {
+                return false;
+            }
+        }
+        true
+    }
+    #[inline(always)]
+    pub fn may_contain_simd(&self, hash: u64) -> bool {
+        let block = Simd::<u32, 8>::from_array(self.blocks[self.block(hash)].0);
+        let shift = (Simd::splat(hash as u32) * Simd::from_array(SALTS)) >> Simd::splat(27);
+        let mask = Simd::splat(1) << shift;
+        (block & mask).simd_eq(mask).all()
+    }
+    /// Appends the indexes of hashes that may be contained to `selection`.
+    ///
+    /// Returns the number of appended indexes.
+    pub fn may_contain_batch(&self, hashes: &[u64], selection: &mut Vec<u32>) -> usize {
+        let start = selection.len();
+        selection.reserve(hashes.len());
+        for (i, &hash) in hashes.iter().enumerate() {
+            if self.may_contain_simd(hash) {
+                selection.push(i as u32);
+            }
+        }
+        selection.len() - start
+    }
+}
@@ -0,0 +1,129 @@
+use crate::hashtable::Hashtable;
+use crate::traits::Key;
+use crate::twolevel_hashtable::{dispatch, insert_into_bucket, TwolevelHashtable, BUCKETS};
+use crate::utils::OnUnwind;
+use std::alloc::Allocator;
+use std::intrinsics::unlikely;
+use std::mem::MaybeUninit;
+use std::sync::{Mutex, MutexGuard};
+
+#[repr(align(64))]
+struct Bucket<K, V, A>(Mutex<Hashtable<K, V, A>>)
+where
+    K: Key,
+    A: Allocator + Clone;
+
+/// A two-level hash table whose buckets are guarded by their own locks,
+/// so that it could be built and probed by many threads at the same time.
+pub struct ConcurrentHashtable<K, V, A = crate::allocator::Default>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    buckets: [Bucket<K, V, A>; BUCKETS],
+}
+
+impl<K, V, A> ConcurrentHashtable<K, V, A>
+where
+    K: Key,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+}
+
+impl<K, V, A> ConcurrentHashtable<K, V, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    /// Buckets are allocated on their first insertions.
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| {
+                Bucket(Mutex::new(Hashtable::empty_in(allocator.clone())))
+            }),
+        }
+    }
+    /// A bucket is poisoned only if a closure panicked while it's locked, and `insert`
+    /// removes the key whose value is not initialized then, so the bucket stays valid.
+    #[inline(always)]
+    fn lock(&self, index: usize) -> MutexGuard<'_, Hashtable<K, V, A>> {
+        self.buckets[index]
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// It's only a snapshot if other threads are inserting.
+    pub fn len(&self) -> usize {
+        (0..BUCKETS).map(|i| self.lock(i).len()).sum::<usize>()
+    }
+    /// It's only a snapshot if other threads are inserting.
+    pub fn capacity(&self) -> usize {
+        (0..BUCKETS).map(|i| self.lock(i).capacity()).sum::<usize>()
+    }
+    /// `f` is called while the bucket of `key` is locked.
+    #[inline(always)]
+    pub fn get<F, R>(&self, key: &K, f: F) -> R
+    where
+        F: FnOnce(Option<&V>) -> R,
+    {
+        let hash = K::hash(key);
+        let bucket = self.lock(dispatch(hash));
+        if unlikely(K::equals_zero(key)) {
+            return f(bucket.get(key));
+        }
+        f(unsafe { bucket.table.get_with_hash(key, hash) })
+    }
+    /// `f` is called while the bucket of `key` is locked.
+    #[inline(always)]
+    pub fn get_mut<F, R>(&self, key: &K, f: F) -> R
+    where
+        F: FnOnce(Option<&mut V>) -> R,
+    {
+        let hash = K::hash(key);
+        let mut bucket = self.lock(dispatch(hash));
+        if unlikely(K::equals_zero(key)) {
+            return f(bucket.get_mut(key));
+        }
+        f(unsafe { bucket.table.get_with_hash_mut(key, hash) })
+    }
+    /// `f` is called while the bucket of `key` is locked. If `f` panics on a new key,
+    /// the key is removed.
+    ///
+    /// # Safety
+    ///
+    /// The `MaybeUninit` passed to `f` should be initialized by `f`.
+    #[inline(always)]
+    pub unsafe fn insert<F, R>(&self, key: K, f: F) -> R
+    where
+        F: FnOnce(Result<&mut MaybeUninit<V>, &mut V>) -> R,
+    {
+        let hash = K::hash(&key);
+        let mut bucket = self.lock(dispatch(hash));
+        let table: *mut Hashtable<K, V, A> = &mut *bucket;
+        match insert_into_bucket(&mut *table, key, hash) {
+            Ok(x) => {
+                let guard = OnUnwind(|| (*table).unregister(key));
+                let result = f(Ok(x));
+                std::mem::forget(guard);
+                result
+            }
+            Err(x) => f(Err(x)),
+        }
+    }
+    /// Returns the table as a `TwolevelHashtable` after all threads finished.
+    pub fn into_twolevel(self) -> TwolevelHashtable<K, V, A> {
+        TwolevelHashtable::from_buckets(self.buckets.map(|bucket| {
+            bucket
+                .0
+                .into_inner()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+        }))
+    }
+}
@@ -60,6 +60,11 @@ pub struct StackContainer<T, const N: usize, A: Allocator> {
     array: [MaybeUninit<T>; N],
 }
 
+// `ptr` is owned by the container, like the pointer in a `Box`.
+unsafe impl<T: Send, const N: usize, A: Allocator + Send> Send for StackContainer<T, N, A> {}
+
+unsafe impl<T: Sync, const N: usize, A: Allocator + Sync> Sync for StackContainer<T, N, A> {}
+
 impl<T, const N: usize, A: Allocator> Deref for StackContainer<T, N, A> {
     type Target = [T];
 
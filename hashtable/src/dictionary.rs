@@ -0,0 +1,105 @@
+use crate::group_index::UnsizedGroupIndex;
+use crate::string_column::Offset;
+use crate::traits::UnsizedKey;
+use std::alloc::Allocator;
+
+/// A bidirectional map between strings and dense codes for dictionary encoding.
+///
+/// Codes are assigned in first-seen order. Strings are stored once in an arena and
+/// located by their codes, so decoding is a lookup.
+pub struct StringDictionary<K = str, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    index: UnsizedGroupIndex<K, A>,
+}
+
+impl<K, A> StringDictionary<K, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            index: UnsizedGroupIndex::new_in(allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+    /// Returns the code of `key`, adding it to the dictionary if it's absent.
+    #[inline(always)]
+    pub fn encode(&mut self, key: &K) -> u32 {
+        self.index.insert(key)
+    }
+    /// Appends codes of all keys of a column to `codes`.
+    pub fn encode_column<'a, I>(&mut self, keys: I, codes: &mut Vec<u32>)
+    where
+        I: IntoIterator<Item = &'a K>,
+        K: 'a,
+    {
+        let keys = keys.into_iter();
+        codes.reserve(keys.size_hint().0);
+        for key in keys {
+            codes.push(self.index.insert(key));
+        }
+    }
+    /// Returns the code of `key` without adding it.
+    #[inline(always)]
+    pub fn lookup(&self, key: &K) -> Option<u32> {
+        self.index.get(key)
+    }
+    /// # Panics
+    ///
+    /// Panics if `code` is not in the dictionary.
+    #[inline(always)]
+    pub fn decode(&self, code: u32) -> &K {
+        self.index.key(code)
+    }
+    /// Appends the decoded column to `offsets` and `data`, which could be read back by
+    /// `StringColumn`.
+    ///
+    /// `offsets` should be empty or end with `data.len()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of `codes` is not in the dictionary, or if offsets overflow.
+    pub fn decode_column<O: Offset>(
+        &self,
+        codes: &[u32],
+        offsets: &mut Vec<O>,
+        data: &mut Vec<u8>,
+    ) {
+        if offsets.is_empty() {
+            offsets.push(O::from_usize(data.len()));
+        }
+        offsets.reserve(codes.len());
+        for &code in codes.iter() {
+            data.extend_from_slice(self.index.key(code).as_bytes());
+            offsets.push(O::from_usize(data.len()));
+        }
+    }
+    /// Returns all keys in the order of their codes.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.index.keys()
+    }
+    /// Adds all keys of `other` to `self`.
+    ///
+    /// Returns the remap table, whose `i`-th element is the new code of code `i` in `other`.
+    pub fn merge(&mut self, other: &Self) -> Vec<u32> {
+        let mut remap = Vec::with_capacity(other.len());
+        for key in other.keys() {
+            remap.push(self.index.insert(key));
+        }
+        remap
+    }
+}
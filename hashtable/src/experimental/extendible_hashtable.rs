@@ -1,6 +1,6 @@
 use crate::container::HeapContainer;
 use crate::table0::{Slot, Table0};
-use crate::traits::Key;
+use crate::traits::{HashtableLike, Key};
 use std::alloc::Allocator;
 use std::intrinsics::unlikely;
 use std::mem::MaybeUninit;
@@ -120,6 +120,29 @@ where
             }
         }
     }
+    /// Entries of `other` are inserted one by one, so buckets of `self` are split as usual.
+    ///
+    /// # Safety
+    ///
+    /// `f` should initialize the resulted `MaybeUninit` immedidately.
+    pub unsafe fn merge<F>(&mut self, mut other: Self, mut f: F)
+    where
+        F: FnMut(K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        if let Some(Slot { key, val, .. }) = other.zero.take() {
+            let key = key.assume_init();
+            let val = val.assume_init();
+            f(key, self.insert(key), val);
+        }
+        for (_, table) in other.tables.iter_mut() {
+            table.dropped = true;
+            for slot in table.iter_raw_mut() {
+                let key = *slot.key.assume_init_ref();
+                let result = self.insert(key);
+                f(key, result, slot.val.assume_init_read());
+            }
+        }
+    }
     pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
         self.tables.iter().flat_map(|x| x.1.iter()).chain(
             self.zero
@@ -141,3 +164,53 @@ where
         self.tables.len()
     }
 }
+
+impl<K, V, A> HashtableLike for ExtendibleHashtable<K, V, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    type Key = K;
+    type Value = V;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+
+    unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        self.insert(*key)
+    }
+
+    unsafe fn merge<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        self.merge(other, |key, result, value| f(&key, result, value))
+    }
+
+    type Iter<'a>
+        = impl Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a;
+
+    type IterMut<'a>
+        = impl Iterator<Item = (&'a K, &'a mut V)>
+    where
+        Self: 'a;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.iter_mut()
+    }
+}
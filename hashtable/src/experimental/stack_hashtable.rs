@@ -1,6 +1,6 @@
 use crate::container::StackContainer;
 use crate::table0::{Slot, Table0};
-use crate::traits::Key;
+use crate::traits::{HashtableLike, Key};
 use std::alloc::Allocator;
 use std::intrinsics::unlikely;
 use std::mem::MaybeUninit;
@@ -95,7 +95,87 @@ where
         }
         self.table.insert(key)
     }
+    #[inline(always)]
+    pub unsafe fn merge<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        if let Some(Slot { key, val, .. }) = other.zero {
+            let key = key.assume_init();
+            let val = val.assume_init();
+            f(key, self.insert(key), val);
+        }
+        while (self.table.len() + other.table.len()) * 2 > self.table.capacity() {
+            if (self.table.slots.len() >> 22) == 0 {
+                self.table.grow(2);
+            } else {
+                self.table.grow(1);
+            }
+        }
+        self.table.merge(other.table, f);
+    }
     pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
-        self.table.iter()
+        self.table.iter().chain(
+            self.zero
+                .iter()
+                .map(|x| unsafe { (x.key.assume_init_ref(), x.val.assume_init_ref()) }),
+        )
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.table.iter_mut().chain(
+            self.zero
+                .iter_mut()
+                .map(|x| unsafe { (x.key.assume_init_ref(), x.val.assume_init_mut()) }),
+        )
+    }
+}
+
+impl<K, V, A, const N: usize> HashtableLike for StackHashtable<K, V, N, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    type Key = K;
+    type Value = V;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+
+    unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        self.insert(*key)
+    }
+
+    unsafe fn merge<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        self.merge(other, |key, result, value| f(&key, result, value))
+    }
+
+    type Iter<'a>
+        = impl Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a;
+
+    type IterMut<'a>
+        = impl Iterator<Item = (&'a K, &'a mut V)>
+    where
+        Self: 'a;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.iter_mut()
     }
 }
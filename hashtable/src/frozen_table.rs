@@ -0,0 +1,72 @@
+use crate::hashtable::Hashtable;
+use crate::traits::{Key, UnsizedKey};
+use crate::unsized_hashtable::UnsizedHashtable;
+use std::alloc::Allocator;
+
+/// A read-only view of a built table, which could be shared by threads through `Arc`.
+///
+/// It's created by `Hashtable::freeze` or `UnsizedHashtable::freeze`.
+pub struct FrozenTable<T> {
+    table: T,
+}
+
+impl<T> FrozenTable<T> {
+    pub(crate) fn new(table: T) -> Self {
+        Self { table }
+    }
+    pub fn into_inner(self) -> T {
+        self.table
+    }
+}
+
+impl<K, V, A> FrozenTable<Hashtable<K, V, A>>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.table.get(key)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.table.iter()
+    }
+}
+
+impl<K, V, A> FrozenTable<UnsizedHashtable<K, V, A>>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.table.get(key)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.table.iter()
+    }
+}
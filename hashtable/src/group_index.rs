@@ -0,0 +1,194 @@
+use crate::hashtable::Hashtable;
+use crate::string_column::{Offset, StringColumn};
+use crate::traits::{Key, UnsizedKey};
+use crate::unsized_hashtable::UnsizedHashtable;
+use std::alloc::Allocator;
+use std::ptr::NonNull;
+
+/// Maps keys to dense group ids, which are assigned in first-seen order.
+pub struct GroupIndex<K, A = crate::allocator::Default>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    table: Hashtable<K, u32, A>,
+    keys: Vec<K, A>,
+    ids: Vec<u32, A>,
+}
+
+impl<K, A> GroupIndex<K, A>
+where
+    K: Key,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+}
+
+impl<K, A> GroupIndex<K, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            table: Hashtable::new_in(allocator.clone()),
+            keys: Vec::new_in(allocator.clone()),
+            ids: Vec::new_in(allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+    /// Returns the group id of `key`.
+    #[inline(always)]
+    pub fn insert(&mut self, key: K) -> u32 {
+        match unsafe { self.table.insert(key) } {
+            Ok(x) => {
+                let id = self.keys.len() as u32;
+                assert_ne!(id, u32::MAX, "the group index overflows");
+                self.keys.push(key);
+                *x.write(id)
+            }
+            Err(x) => *x,
+        }
+    }
+    /// Returns group ids of `keys`.
+    pub fn insert_batch(&mut self, keys: &[K]) -> &[u32] {
+        self.ids.clear();
+        self.ids.reserve(keys.len());
+        for &key in keys.iter() {
+            let id = self.insert(key);
+            self.ids.push(id);
+        }
+        &self.ids
+    }
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<u32> {
+        self.table.get(key).copied()
+    }
+    #[inline(always)]
+    pub fn key(&self, id: u32) -> &K {
+        &self.keys[id as usize]
+    }
+    /// Returns all keys in the order of their group ids.
+    pub fn keys(&self) -> &[K] {
+        &self.keys
+    }
+}
+
+/// The group id of null rows.
+pub const NULL_GROUP: u32 = u32::MAX;
+
+/// Maps unsized keys to dense group ids, which are assigned in first-seen order.
+///
+/// Keys are stored once in the arena of the table, and located by their group ids.
+pub struct UnsizedGroupIndex<K, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    table: UnsizedHashtable<K, u32, A>,
+    keys: Vec<NonNull<[u8]>, A>,
+    ids: Vec<u32, A>,
+}
+
+// `keys` point to bytes in the arena of the table, which are never mutated.
+unsafe impl<K, A> Send for UnsizedGroupIndex<K, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone + Send,
+{
+}
+
+unsafe impl<K, A> Sync for UnsizedGroupIndex<K, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone + Sync,
+{
+}
+
+impl<K, A> UnsizedGroupIndex<K, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            table: UnsizedHashtable::new_in(allocator.clone()),
+            keys: Vec::new_in(allocator.clone()),
+            ids: Vec::new_in(allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+    /// Returns the group id of `key`.
+    #[inline(always)]
+    pub fn insert(&mut self, key: &K) -> u32 {
+        match unsafe { self.table.insert_copied(key) } {
+            Ok((key, x)) => {
+                let id = self.keys.len() as u32;
+                assert_ne!(id, u32::MAX, "the group index overflows");
+                self.keys.push(key);
+                *x.write(id)
+            }
+            Err(x) => *x,
+        }
+    }
+    /// Returns group ids of `keys`.
+    pub fn insert_batch(&mut self, keys: &[&K]) -> &[u32] {
+        self.ids.clear();
+        self.ids.reserve(keys.len());
+        for &key in keys.iter() {
+            let id = self.insert(key);
+            self.ids.push(id);
+        }
+        &self.ids
+    }
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<u32> {
+        self.table.get(key).copied()
+    }
+    #[inline(always)]
+    pub fn key(&self, id: u32) -> &K {
+        unsafe { K::from_bytes(self.keys[id as usize].as_ref()) }
+    }
+    /// Returns all keys in the order of their group ids.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        (0..self.len() as u32).map(|id| self.key(id))
+    }
+}
+
+impl<A> UnsizedGroupIndex<[u8], A>
+where
+    A: Allocator + Clone + Default,
+{
+    /// Returns group ids of all values of `column`, and `NULL_GROUP` for null rows.
+    pub fn insert_column<O: Offset>(&mut self, column: &StringColumn<'_, O>) -> &[u32] {
+        self.ids.clear();
+        self.ids.reserve(column.len());
+        for key in column.iter() {
+            let id = match key {
+                Some(key) => self.insert(key),
+                None => NULL_GROUP,
+            };
+            self.ids.push(id);
+        }
+        &self.ids
+    }
+}
@@ -1,4 +1,5 @@
 use crate::traits::FastHash;
+use primitive_types::U256;
 use std::num::NonZeroU64;
 
 const CRC_A: u32 = u32::MAX;
@@ -41,6 +42,45 @@ impl_fast_hash_for_primitive_types!(i32);
 impl_fast_hash_for_primitive_types!(u64);
 impl_fast_hash_for_primitive_types!(i64);
 
+impl FastHash for u128 {
+    #[inline(always)]
+    fn fast_hash(&self) -> u64 {
+        [*self as u64, (*self >> 64) as u64].fast_hash()
+    }
+}
+
+impl FastHash for U256 {
+    #[inline(always)]
+    fn fast_hash(&self) -> u64 {
+        self.0.fast_hash()
+    }
+}
+
+impl<const N: usize> FastHash for [u64; N] {
+    #[inline(always)]
+    fn fast_hash(&self) -> u64 {
+        cfg_if::cfg_if! {
+            if #[cfg(target_feature = "sse4.2")] {
+                use std::arch::x86_64::_mm_crc32_u64;
+                let mut high = CRC_A;
+                let mut low = CRC_B;
+                for x in *self {
+                    high = unsafe { _mm_crc32_u64(high as u64, x) as u32 };
+                    low = unsafe { _mm_crc32_u64(low as u64, x) as u32 };
+                }
+                (high as u64) << 32 | low as u64
+            } else {
+                use std::hash::Hasher;
+                let mut hasher = ahash::AHasher::default();
+                for x in *self {
+                    hasher.write_u64(x);
+                }
+                hasher.finish()
+            }
+        }
+    }
+}
+
 impl<const N: usize> FastHash for ([u64; N], NonZeroU64) {
     #[inline(always)]
     fn fast_hash(&self) -> u64 {
@@ -0,0 +1,384 @@
+use crate::hashtable::Hashtable;
+use crate::key_packing::{is_valid, pack, packed_size, Column, FixedColumn, PackedKey};
+use crate::row_key::RowKeyWriter;
+use crate::table1::Table1;
+use crate::unsized_hashtable::UnsizedHashtable;
+use primitive_types::U256;
+use std::alloc::Allocator;
+use std::mem::MaybeUninit;
+
+/// The type of a key column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyColumn {
+    Fixed(FixedColumn),
+    Bytes { nullable: bool },
+}
+
+/// A key column of a batch.
+#[derive(Clone, Copy)]
+pub enum ColumnData<'a> {
+    Fixed(Column<'a>),
+    /// Null bitmaps are LSB-first, and a set bit means that the value is valid.
+    Bytes {
+        values: &'a [&'a [u8]],
+        validity: Option<&'a [u8]>,
+    },
+}
+
+impl<'a> ColumnData<'a> {
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnData::Fixed(x) => x.len(),
+            ColumnData::Bytes { values, .. } => values.len(),
+        }
+    }
+    pub fn describe(&self) -> KeyColumn {
+        match self {
+            ColumnData::Fixed(x) => KeyColumn::Fixed(x.describe()),
+            ColumnData::Bytes { validity, .. } => KeyColumn::Bytes {
+                nullable: validity.is_some(),
+            },
+        }
+    }
+}
+
+/// The way keys of a schema are hashed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashMethod {
+    /// A direct-mapped table for one 1-byte or 2-byte column.
+    DirectMapped,
+    /// Fixed-width columns packed into a primitive key.
+    Keys8,
+    Keys16,
+    Keys32,
+    Keys64,
+    Keys128,
+    Keys256,
+    /// One variable-length column.
+    Bytes,
+    /// Columns serialized by `RowKeyWriter`.
+    Serialized,
+}
+
+impl HashMethod {
+    pub fn choose(schema: &[KeyColumn]) -> Self {
+        if let [KeyColumn::Fixed(FixedColumn {
+            width: 1 | 2,
+            nullable: false,
+        })] = schema
+        {
+            return HashMethod::DirectMapped;
+        }
+        if let [KeyColumn::Bytes { nullable: false }] = schema {
+            return HashMethod::Bytes;
+        }
+        let fixed = schema
+            .iter()
+            .map(|x| match x {
+                KeyColumn::Fixed(x) => Some(*x),
+                KeyColumn::Bytes { .. } => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        match fixed.map(|x| packed_size(&x)) {
+            Some(0..=1) => HashMethod::Keys8,
+            Some(2) => HashMethod::Keys16,
+            Some(3..=4) => HashMethod::Keys32,
+            Some(5..=8) => HashMethod::Keys64,
+            Some(9..=16) => HashMethod::Keys128,
+            Some(17..=32) => HashMethod::Keys256,
+            _ => HashMethod::Serialized,
+        }
+    }
+}
+
+/// A key yielded by `DispatchedHashtable::iter`.
+///
+/// Packed keys could be unpacked by `key_packing::unpack`, and serialized keys
+/// could be read by `RowKeyReader`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRef<'a> {
+    Keys8(u8),
+    Keys16(u16),
+    Keys32(u32),
+    Keys64(u64),
+    Keys128(u128),
+    Keys256(U256),
+    Bytes(&'a [u8]),
+}
+
+enum Table<V, A: Allocator + Clone> {
+    DirectMapped(Table1<V, A>),
+    Keys8(Hashtable<u8, V, A>),
+    Keys16(Hashtable<u16, V, A>),
+    Keys32(Hashtable<u32, V, A>),
+    Keys64(Hashtable<u64, V, A>),
+    Keys128(Hashtable<u128, V, A>),
+    Keys256(Hashtable<U256, V, A>),
+    Bytes(UnsizedHashtable<[u8], V, A>),
+    Serialized(UnsizedHashtable<[u8], V, A>),
+}
+
+/// A table whose key type is chosen by `HashMethod::choose` from a key schema.
+pub struct DispatchedHashtable<V, A = crate::allocator::Default>
+where
+    A: Allocator + Clone,
+{
+    schema: Vec<KeyColumn>,
+    method: HashMethod,
+    table: Table<V, A>,
+    writer: RowKeyWriter<A>,
+}
+
+impl<V, A> DispatchedHashtable<V, A>
+where
+    A: Allocator + Clone + Default,
+{
+    pub fn new(schema: &[KeyColumn]) -> Self {
+        Self::new_in(schema, Default::default())
+    }
+}
+
+impl<V, A> DispatchedHashtable<V, A>
+where
+    A: Allocator + Clone,
+{
+    pub fn new_in(schema: &[KeyColumn], allocator: A) -> Self {
+        let method = HashMethod::choose(schema);
+        let a = allocator.clone();
+        let table = match method {
+            HashMethod::DirectMapped => Table::DirectMapped(Table1::new_in(a)),
+            HashMethod::Keys8 => Table::Keys8(Hashtable::new_in(a)),
+            HashMethod::Keys16 => Table::Keys16(Hashtable::new_in(a)),
+            HashMethod::Keys32 => Table::Keys32(Hashtable::new_in(a)),
+            HashMethod::Keys64 => Table::Keys64(Hashtable::new_in(a)),
+            HashMethod::Keys128 => Table::Keys128(Hashtable::new_in(a)),
+            HashMethod::Keys256 => Table::Keys256(Hashtable::new_in(a)),
+            HashMethod::Bytes => Table::Bytes(UnsizedHashtable::new_in(a)),
+            HashMethod::Serialized => Table::Serialized(UnsizedHashtable::new_in(a)),
+        };
+        Self {
+            schema: schema.to_vec(),
+            method,
+            table,
+            writer: RowKeyWriter::new_in(allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn method(&self) -> HashMethod {
+        self.method
+    }
+    #[inline(always)]
+    pub fn schema(&self) -> &[KeyColumn] {
+        &self.schema
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn len(&self) -> usize {
+        match &self.table {
+            Table::DirectMapped(x) => x.len(),
+            Table::Keys8(x) => x.len(),
+            Table::Keys16(x) => x.len(),
+            Table::Keys32(x) => x.len(),
+            Table::Keys64(x) => x.len(),
+            Table::Keys128(x) => x.len(),
+            Table::Keys256(x) => x.len(),
+            Table::Bytes(x) => x.len(),
+            Table::Serialized(x) => x.len(),
+        }
+    }
+    /// Inserts keys of all rows of `columns`, and calls `f` with indexes of rows and
+    /// insertion results.
+    ///
+    /// # Safety
+    ///
+    /// `f` should initialize the resulted `MaybeUninit` immedidately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `columns` don't match the schema.
+    pub unsafe fn insert_batch<F>(&mut self, columns: &[ColumnData<'_>], mut f: F)
+    where
+        F: FnMut(usize, Result<&mut MaybeUninit<V>, &mut V>),
+    {
+        let rows = self.check(columns);
+        match &mut self.table {
+            Table::DirectMapped(table) => {
+                for (i, key) in packed::<u16>(columns, rows).into_iter().enumerate() {
+                    f(i, table.insert(key.to_le_bytes()));
+                }
+            }
+            Table::Keys8(table) => insert_packed(table, columns, rows, f),
+            Table::Keys16(table) => insert_packed(table, columns, rows, f),
+            Table::Keys32(table) => insert_packed(table, columns, rows, f),
+            Table::Keys64(table) => insert_packed(table, columns, rows, f),
+            Table::Keys128(table) => insert_packed(table, columns, rows, f),
+            Table::Keys256(table) => insert_packed(table, columns, rows, f),
+            Table::Bytes(table) => {
+                let ColumnData::Bytes { values, .. } = columns[0] else {
+                    unreachable!()
+                };
+                for (i, &key) in values[..rows].iter().enumerate() {
+                    f(i, table.insert(key));
+                }
+            }
+            Table::Serialized(table) => {
+                serialize(&mut self.writer, columns, rows);
+                for (i, key) in self.writer.keys().enumerate() {
+                    f(i, table.insert(key));
+                }
+            }
+        }
+    }
+    /// Looks up keys of all rows of `columns`, and calls `f` with indexes of rows and
+    /// the values.
+    ///
+    /// Serialized keys are written into `scratch`, which is cleared first, so that callers
+    /// could reuse one buffer across calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `columns` don't match the schema.
+    pub fn get_batch<F>(&self, columns: &[ColumnData<'_>], scratch: &mut RowKeyWriter<A>, mut f: F)
+    where
+        F: FnMut(usize, Option<&V>),
+    {
+        let rows = self.check(columns);
+        match &self.table {
+            Table::DirectMapped(table) => {
+                for (i, key) in packed::<u16>(columns, rows).into_iter().enumerate() {
+                    f(i, table.get(key.to_le_bytes()));
+                }
+            }
+            Table::Keys8(table) => get_packed(table, columns, rows, f),
+            Table::Keys16(table) => get_packed(table, columns, rows, f),
+            Table::Keys32(table) => get_packed(table, columns, rows, f),
+            Table::Keys64(table) => get_packed(table, columns, rows, f),
+            Table::Keys128(table) => get_packed(table, columns, rows, f),
+            Table::Keys256(table) => get_packed(table, columns, rows, f),
+            Table::Bytes(table) => {
+                let ColumnData::Bytes { values, .. } = columns[0] else {
+                    unreachable!()
+                };
+                for (i, key) in values[..rows].iter().enumerate() {
+                    f(i, table.get(key));
+                }
+            }
+            Table::Serialized(table) => {
+                serialize(scratch, columns, rows);
+                for (i, key) in scratch.keys().enumerate() {
+                    f(i, table.get(key));
+                }
+            }
+        }
+    }
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (KeyRef<'_>, &V)> + '_> {
+        match &self.table {
+            Table::DirectMapped(x) => Box::new(
+                x.iter()
+                    .map(|(k, v)| (KeyRef::Keys16(u16::from_le_bytes(*k)), v)),
+            ),
+            Table::Keys8(x) => Box::new(x.iter().map(|(k, v)| (KeyRef::Keys8(*k), v))),
+            Table::Keys16(x) => Box::new(x.iter().map(|(k, v)| (KeyRef::Keys16(*k), v))),
+            Table::Keys32(x) => Box::new(x.iter().map(|(k, v)| (KeyRef::Keys32(*k), v))),
+            Table::Keys64(x) => Box::new(x.iter().map(|(k, v)| (KeyRef::Keys64(*k), v))),
+            Table::Keys128(x) => Box::new(x.iter().map(|(k, v)| (KeyRef::Keys128(*k), v))),
+            Table::Keys256(x) => Box::new(x.iter().map(|(k, v)| (KeyRef::Keys256(*k), v))),
+            Table::Bytes(x) | Table::Serialized(x) => {
+                Box::new(x.iter().map(|(k, v)| (KeyRef::Bytes(k), v)))
+            }
+        }
+    }
+    fn check(&self, columns: &[ColumnData<'_>]) -> usize {
+        assert!(
+            columns
+                .iter()
+                .map(ColumnData::describe)
+                .eq(self.schema.iter().copied()),
+            "columns don't match the schema"
+        );
+        let rows = columns.iter().map(ColumnData::len).min().unwrap_or(0);
+        assert!(
+            columns.iter().all(|x| x.len() == rows),
+            "columns have different lengths"
+        );
+        rows
+    }
+}
+
+fn fixed<'a>(columns: &[ColumnData<'a>]) -> Vec<Column<'a>> {
+    columns
+        .iter()
+        .map(|x| match x {
+            ColumnData::Fixed(x) => *x,
+            ColumnData::Bytes { .. } => unreachable!(),
+        })
+        .collect()
+}
+
+fn packed<K: PackedKey>(columns: &[ColumnData<'_>], rows: usize) -> Vec<K> {
+    let mut keys = vec![K::default(); rows];
+    pack(&fixed(columns), &mut keys);
+    keys
+}
+
+unsafe fn insert_packed<K, V, A, F>(
+    table: &mut Hashtable<K, V, A>,
+    columns: &[ColumnData<'_>],
+    rows: usize,
+    mut f: F,
+) where
+    K: PackedKey,
+    A: Allocator + Clone,
+    F: FnMut(usize, Result<&mut MaybeUninit<V>, &mut V>),
+{
+    for (i, key) in packed::<K>(columns, rows).into_iter().enumerate() {
+        f(i, table.insert(key));
+    }
+}
+
+fn get_packed<K, V, A, F>(
+    table: &Hashtable<K, V, A>,
+    columns: &[ColumnData<'_>],
+    rows: usize,
+    mut f: F,
+) where
+    K: PackedKey,
+    A: Allocator + Clone,
+    F: FnMut(usize, Option<&V>),
+{
+    for (i, key) in packed::<K>(columns, rows).iter().enumerate() {
+        f(i, table.get(key));
+    }
+}
+
+fn serialize<A: Allocator + Clone>(
+    writer: &mut RowKeyWriter<A>,
+    columns: &[ColumnData<'_>],
+    rows: usize,
+) {
+    writer.clear();
+    for i in 0..rows {
+        for column in columns.iter() {
+            match *column {
+                ColumnData::Fixed(x) => {
+                    let value = x.value(i);
+                    if x.describe().nullable {
+                        writer.write_flag(value.is_some());
+                    }
+                    if let Some(value) = value {
+                        writer.write_raw(value);
+                    }
+                }
+                ColumnData::Bytes { values, validity } => match validity {
+                    None => writer.write_bytes(values[i]),
+                    Some(_) => {
+                        writer.write_nullable_bytes(is_valid(validity, i).then(|| values[i]))
+                    }
+                },
+            }
+        }
+        writer.finish_row();
+    }
+}
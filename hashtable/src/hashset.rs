@@ -0,0 +1,311 @@
+use crate::hashtable::{self, Hashtable};
+use crate::traits::{Key, UnsizedKey};
+use crate::twolevel_hashtable::{self, TwolevelHashtable};
+use crate::unsized_hashtable::UnsizedHashtable;
+use std::alloc::Allocator;
+
+fn key_of<K>((key, _): (K, ())) -> K {
+    key
+}
+
+pub struct Hashset<K, A = crate::allocator::Default>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    pub(crate) table: Hashtable<K, (), A>,
+}
+
+impl<K, A> Hashset<K, A>
+where
+    K: Key,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Default::default())
+    }
+}
+
+impl<K, A> Hashset<K, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            table: Hashtable::new_in(allocator),
+        }
+    }
+    pub fn with_capacity_in(capacity: usize, allocator: A) -> Self {
+        Self {
+            table: Hashtable::with_capacity_in(capacity, allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+    /// Returns `true` if the key is newly inserted.
+    #[inline(always)]
+    pub fn insert(&mut self, key: K) -> bool {
+        unsafe { self.table.insert(key) }
+            .map(|x| {
+                x.write(());
+            })
+            .is_ok()
+    }
+    #[inline(always)]
+    pub fn contains(&self, key: &K) -> bool {
+        self.table.get(key).is_some()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        self.table.iter().map(|(key, _)| key)
+    }
+    /// Moves all keys of `other` into `self`.
+    pub fn union(&mut self, other: Self) {
+        unsafe {
+            self.table.merge(other.table, |_, result, _| {
+                if let Ok(x) = result {
+                    x.write(());
+                }
+            });
+        }
+    }
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (small, large) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut result = Self::new_in(self.table.table.allocator.clone());
+        for key in small.iter() {
+            if large.contains(key) {
+                result.insert(*key);
+            }
+        }
+        result
+    }
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Self::new_in(self.table.table.allocator.clone());
+        for key in self.iter() {
+            if !other.contains(key) {
+                result.insert(*key);
+            }
+        }
+        result
+    }
+}
+
+impl<K, A> IntoIterator for Hashset<K, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    type Item = K;
+
+    type IntoIter = std::iter::Map<hashtable::IntoIter<K, (), A>, fn((K, ())) -> K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.table.into_iter().map(key_of)
+    }
+}
+
+pub struct TwolevelHashset<K, A = crate::allocator::Default>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    pub(crate) table: TwolevelHashtable<K, (), A>,
+}
+
+impl<K, A> TwolevelHashset<K, A>
+where
+    K: Key,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+}
+
+impl<K, A> TwolevelHashset<K, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            table: TwolevelHashtable::new_in(allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+    /// Returns `true` if the key is newly inserted.
+    #[inline(always)]
+    pub fn insert(&mut self, key: K) -> bool {
+        unsafe { self.table.insert(key) }
+            .map(|x| {
+                x.write(());
+            })
+            .is_ok()
+    }
+    #[inline(always)]
+    pub fn contains(&self, key: &K) -> bool {
+        self.table.get(key).is_some()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        self.table.iter().map(|(key, _)| key)
+    }
+    /// Moves all keys of `other` into `self` bucket by bucket.
+    pub fn union(&mut self, other: Self) {
+        unsafe {
+            self.table.merge(other.table, |_, result, _| {
+                if let Ok(x) = result {
+                    x.write(());
+                }
+            });
+        }
+    }
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (small, large) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut result = Self::new_in(self.table.bucket(0).table.allocator.clone());
+        for key in small.iter() {
+            if large.contains(key) {
+                result.insert(*key);
+            }
+        }
+        result
+    }
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Self::new_in(self.table.bucket(0).table.allocator.clone());
+        for key in self.iter() {
+            if !other.contains(key) {
+                result.insert(*key);
+            }
+        }
+        result
+    }
+}
+
+impl<K, A> IntoIterator for TwolevelHashset<K, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    type Item = K;
+
+    type IntoIter = std::iter::Map<twolevel_hashtable::IntoIter<K, (), A>, fn((K, ())) -> K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.table.into_iter().map(key_of)
+    }
+}
+
+pub struct UnsizedHashset<K, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    pub(crate) table: UnsizedHashtable<K, (), A>,
+}
+
+impl<K, A> UnsizedHashset<K, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            table: UnsizedHashtable::new_in(allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+    /// Returns `true` if the key is newly inserted.
+    #[inline(always)]
+    pub fn insert(&mut self, key: &K) -> bool {
+        unsafe { self.table.insert(key) }
+            .map(|x| {
+                x.write(());
+            })
+            .is_ok()
+    }
+    #[inline(always)]
+    pub fn contains(&self, key: &K) -> bool {
+        self.table.get(key).is_some()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        self.table.iter().map(|(key, _)| key)
+    }
+    /// Moves all keys of `other` into `self`, taking over its arena.
+    pub fn union(&mut self, other: Self) {
+        unsafe {
+            self.table.merge(other.table, |_, result, _| {
+                if let Ok(x) = result {
+                    x.write(());
+                }
+            });
+        }
+    }
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (small, large) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut result = Self::new_in(self.table.tiers.allocator().clone());
+        for key in small.iter() {
+            if large.contains(key) {
+                result.insert(key);
+            }
+        }
+        result
+    }
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = Self::new_in(self.table.tiers.allocator().clone());
+        for key in self.iter() {
+            if !other.contains(key) {
+                result.insert(key);
+            }
+        }
+        result
+    }
+}
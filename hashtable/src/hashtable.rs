@@ -1,11 +1,13 @@
+use crate::bloom_filter::BloomFilter;
 use crate::container::HeapContainer;
 use crate::experimental::batch::batch_build;
+use crate::frozen_table::FrozenTable;
 use crate::simd::dynamic_swizzle::DynamicSwizzle;
 use crate::simd::gather::{Gather, SupportedGather};
 use crate::simd::pext::{Pext, SupportedPext};
 use crate::simd::scatter::{Scatter, SupportedScatter};
-use crate::table0::{Slot, Table0};
-use crate::traits::Key;
+use crate::table0::{Slot, Table0, Table0IntoIter};
+use crate::traits::{HashtableLike, Key};
 use core_simd::simd::*;
 use num::traits::AsPrimitive;
 use num::Bounded;
@@ -20,8 +22,8 @@ where
     K: Key,
     A: Allocator + Clone,
 {
-    zero: Option<Slot<K, V>>,
-    table: Table0<K, V, HeapContainer<Slot<K, V>, A>, A>,
+    pub(crate) zero: Option<Slot<K, V>>,
+    pub(crate) table: Table0<K, V, HeapContainer<Slot<K, V>, A>, A>,
 }
 
 impl<K, V, A> Hashtable<K, V, A>
@@ -51,6 +53,13 @@ where
             zero: None,
         }
     }
+    /// Slots are allocated on the first insertion.
+    pub(crate) fn empty_in(allocator: A) -> Self {
+        Self {
+            table: Table0::empty_in(allocator),
+            zero: None,
+        }
+    }
     #[inline(always)]
     pub fn is_empty(&self) -> bool {
         self.len() == 0
@@ -105,6 +114,18 @@ where
         }
         self.table.insert(key)
     }
+    /// Removes `key` without reading its value, if the value is not initialized.
+    ///
+    /// # Safety
+    ///
+    /// The value of `key` is not initialized.
+    pub(crate) unsafe fn unregister(&mut self, key: K) {
+        if unlikely(K::equals_zero(&key)) {
+            self.zero = None;
+        } else {
+            self.table.remove_slot_with_hash(&key, K::hash(&key));
+        }
+    }
     #[inline(always)]
     pub unsafe fn merge<F>(&mut self, other: Self, mut f: F)
     where
@@ -124,8 +145,52 @@ where
         }
         self.table.merge(other.table, f);
     }
+    /// Builds a Bloom filter of `Key::hash` of all keys.
+    pub fn build_bloom_filter(&self, bits_per_key: usize) -> BloomFilter<A> {
+        let mut filter =
+            BloomFilter::with_capacity_in(self.len(), bits_per_key, self.table.allocator.clone());
+        for (key, _) in self.iter() {
+            filter.insert(K::hash(key));
+        }
+        filter
+    }
+    /// Shrinks the table and makes it read-only.
+    pub fn freeze(mut self) -> FrozenTable<Self> {
+        self.table.shrink_to_fit();
+        FrozenTable::new(self)
+    }
+    /// The key equal to zero is yielded last, if it's present.
     pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
-        self.table.iter()
+        self.table.iter().chain(
+            self.zero
+                .iter()
+                .map(|x| unsafe { (x.key.assume_init_ref(), x.val.assume_init_ref()) }),
+        )
+    }
+    /// The key equal to zero is yielded last, if it's present.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.table.iter_mut().chain(
+            self.zero
+                .iter_mut()
+                .map(|x| unsafe { (x.key.assume_init_ref(), x.val.assume_init_mut()) }),
+        )
+    }
+    /// Appends all keys to `keys`, in the order of `iter`.
+    pub fn export_keys(&self, keys: &mut Vec<K>) {
+        keys.reserve(self.len());
+        keys.extend(self.iter().map(|(key, _)| *key));
+    }
+    /// Appends all values to `values`, in the order of `iter`.
+    pub fn export_values(&self, values: &mut Vec<V>)
+    where
+        V: Clone,
+    {
+        values.reserve(self.len());
+        values.extend(self.iter().map(|(_, value)| value.clone()));
+    }
+    /// Returns a cursor exporting keys and values in chunks, in the order of `iter`.
+    pub fn export(&self) -> Export<impl Iterator<Item = (&K, &V)>> {
+        Export { iter: self.iter() }
     }
     pub unsafe fn batch_insert<const LANES: usize, D, F, G>(
         &mut self,
@@ -171,3 +236,124 @@ where
         }
     }
 }
+
+/// A cursor exporting keys and values of a `Hashtable` in chunks.
+pub struct Export<I> {
+    iter: I,
+}
+
+impl<'a, K, V, I> Export<I>
+where
+    K: Key + 'a,
+    V: Clone + 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    /// Appends at most `rows` keys and values, and returns the number of appended rows,
+    /// which is zero only if the export is finished.
+    pub fn next_chunk(&mut self, rows: usize, keys: &mut Vec<K>, values: &mut Vec<V>) -> usize {
+        let mut n = 0;
+        for (key, value) in self.iter.by_ref().take(rows) {
+            keys.push(*key);
+            values.push(value.clone());
+            n += 1;
+        }
+        n
+    }
+}
+
+impl<K, V, A> HashtableLike for Hashtable<K, V, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    type Key = K;
+    type Value = V;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+
+    unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        self.insert(*key)
+    }
+
+    unsafe fn merge<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        self.merge(other, |key, result, value| f(&key, result, value))
+    }
+
+    type Iter<'a>
+        = impl Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a;
+
+    type IterMut<'a>
+        = impl Iterator<Item = (&'a K, &'a mut V)>
+    where
+        Self: 'a;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.iter_mut()
+    }
+}
+
+pub struct IntoIter<K, V, A = crate::allocator::Default>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    zero: Option<Slot<K, V>>,
+    table: Table0IntoIter<K, V, HeapContainer<Slot<K, V>, A>, A>,
+}
+
+impl<K, V, A> Iterator for IntoIter<K, V, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(Slot { key, val, .. }) = self.zero.take() {
+            return Some(unsafe { (key.assume_init(), val.assume_init()) });
+        }
+        self.table.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.table.size_hint();
+        let zero = self.zero.is_some() as usize;
+        (lower + zero, upper.map(|x| x + zero))
+    }
+}
+
+impl<K, V, A> IntoIterator for Hashtable<K, V, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    type Item = (K, V);
+
+    type IntoIter = IntoIter<K, V, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            zero: self.zero,
+            table: Table0IntoIter::new(self.table),
+        }
+    }
+}
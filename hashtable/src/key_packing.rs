@@ -0,0 +1,231 @@
+use crate::traits::Key;
+use primitive_types::U256;
+use std::mem::size_of;
+
+/// Keys which fixed-width columns are packed into.
+///
+/// # Safety
+///
+/// `Self` should be plain bytes, so any bit pattern is valid.
+pub unsafe trait PackedKey: Key + Default {}
+
+unsafe impl PackedKey for u8 {}
+unsafe impl PackedKey for u16 {}
+unsafe impl PackedKey for u32 {}
+unsafe impl PackedKey for u64 {}
+unsafe impl PackedKey for u128 {}
+unsafe impl PackedKey for U256 {}
+
+#[inline(always)]
+fn bytes<K: PackedKey>(key: &K) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(key as *const K as *const u8, size_of::<K>()) }
+}
+
+#[inline(always)]
+fn bytes_mut<K: PackedKey>(key: &mut K) -> &mut [u8] {
+    unsafe { std::slice::from_raw_parts_mut(key as *mut K as *mut u8, size_of::<K>()) }
+}
+
+/// Values of fixed-width columns.
+///
+/// # Safety
+///
+/// `Self` should be plain bytes, so any bit pattern is valid.
+pub unsafe trait Packable: Copy {}
+
+unsafe impl Packable for u8 {}
+unsafe impl Packable for i8 {}
+unsafe impl Packable for u16 {}
+unsafe impl Packable for i16 {}
+unsafe impl Packable for u32 {}
+unsafe impl Packable for i32 {}
+unsafe impl Packable for u64 {}
+unsafe impl Packable for i64 {}
+
+/// The width of a fixed-width column and whether it has a null bitmap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedColumn {
+    pub width: usize,
+    pub nullable: bool,
+}
+
+/// Returns the number of bytes that rows of `columns` are packed into.
+///
+/// Values are packed in order, followed by one null bit for each nullable column.
+pub fn packed_size(columns: &[FixedColumn]) -> usize {
+    let values = columns.iter().map(|x| x.width).sum::<usize>();
+    let nulls = columns.iter().filter(|x| x.nullable).count();
+    values + (nulls + 7) / 8
+}
+
+#[inline(always)]
+pub(crate) fn is_valid(validity: Option<&[u8]>, index: usize) -> bool {
+    match validity {
+        None => true,
+        Some(x) => x[index / 8] & (1 << (index % 8)) != 0,
+    }
+}
+
+/// A fixed-width column to be packed.
+///
+/// Null bitmaps are LSB-first, and a set bit means that the value is valid.
+#[derive(Clone, Copy)]
+pub struct Column<'a> {
+    data: &'a [u8],
+    width: usize,
+    validity: Option<&'a [u8]>,
+}
+
+impl<'a> Column<'a> {
+    pub fn new<T: Packable>(values: &'a [T]) -> Self {
+        let data = unsafe {
+            std::slice::from_raw_parts(values.as_ptr() as *const u8, std::mem::size_of_val(values))
+        };
+        Self {
+            data,
+            width: size_of::<T>(),
+            validity: None,
+        }
+    }
+    pub fn with_validity(self, validity: &'a [u8]) -> Self {
+        Self {
+            validity: Some(validity),
+            ..self
+        }
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.data.len() / self.width
+    }
+    pub fn describe(&self) -> FixedColumn {
+        FixedColumn {
+            width: self.width,
+            nullable: self.validity.is_some(),
+        }
+    }
+    #[inline(always)]
+    pub(crate) fn value(&self, index: usize) -> Option<&'a [u8]> {
+        if is_valid(self.validity, index) {
+            Some(&self.data[index * self.width..(index + 1) * self.width])
+        } else {
+            None
+        }
+    }
+}
+
+/// A fixed-width column to be unpacked into.
+pub struct ColumnMut<'a> {
+    data: &'a mut [u8],
+    width: usize,
+    validity: Option<&'a mut [u8]>,
+}
+
+impl<'a> ColumnMut<'a> {
+    pub fn new<T: Packable>(values: &'a mut [T]) -> Self {
+        let data = unsafe {
+            std::slice::from_raw_parts_mut(
+                values.as_mut_ptr() as *mut u8,
+                std::mem::size_of_val(values),
+            )
+        };
+        Self {
+            data,
+            width: size_of::<T>(),
+            validity: None,
+        }
+    }
+    pub fn with_validity(self, validity: &'a mut [u8]) -> Self {
+        Self {
+            validity: Some(validity),
+            ..self
+        }
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.data.len() / self.width
+    }
+    pub fn describe(&self) -> FixedColumn {
+        FixedColumn {
+            width: self.width,
+            nullable: self.validity.is_some(),
+        }
+    }
+}
+
+/// Packs rows of `columns` into `keys`. Values of null rows are packed as zeros.
+///
+/// Keys up to `u64` can be fed into `Hashtable::batch_insert` directly.
+///
+/// # Panics
+///
+/// Panics if rows don't fit in `K`, or a column or a null bitmap is shorter than `keys`.
+pub fn pack<K: PackedKey>(columns: &[Column<'_>], keys: &mut [K]) {
+    let layout = columns.iter().map(Column::describe).collect::<Vec<_>>();
+    assert!(
+        packed_size(&layout) <= size_of::<K>(),
+        "keys are too narrow"
+    );
+    keys.fill(K::default());
+    let mut offset = 0;
+    let mut null_offset = layout.iter().map(|x| x.width).sum::<usize>() * 8;
+    for column in columns.iter() {
+        let width = column.width;
+        assert!(column.len() >= keys.len(), "the column is too short");
+        match column.validity {
+            None => {
+                for (key, value) in keys.iter_mut().zip(column.data.chunks_exact(width)) {
+                    bytes_mut(key)[offset..offset + width].copy_from_slice(value);
+                }
+            }
+            Some(validity) => {
+                assert!(validity.len() * 8 >= keys.len(), "the bitmap is too short");
+                for (i, key) in keys.iter_mut().enumerate() {
+                    let key = bytes_mut(key);
+                    if is_valid(Some(validity), i) {
+                        key[offset..offset + width]
+                            .copy_from_slice(&column.data[i * width..(i + 1) * width]);
+                    } else {
+                        key[null_offset / 8] |= 1 << (null_offset % 8);
+                    }
+                }
+                null_offset += 1;
+            }
+        }
+        offset += width;
+    }
+}
+
+/// Unpacks `keys` into `columns`, which should be laid out the same as the packed ones.
+///
+/// # Panics
+///
+/// Panics if rows don't fit in `K`, or a column or a null bitmap is shorter than `keys`.
+pub fn unpack<K: PackedKey>(keys: &[K], columns: &mut [ColumnMut<'_>]) {
+    let layout = columns.iter().map(ColumnMut::describe).collect::<Vec<_>>();
+    assert!(
+        packed_size(&layout) <= size_of::<K>(),
+        "keys are too narrow"
+    );
+    let mut offset = 0;
+    let mut null_offset = layout.iter().map(|x| x.width).sum::<usize>() * 8;
+    for column in columns.iter_mut() {
+        let width = column.width;
+        assert!(column.len() >= keys.len(), "the column is too short");
+        for (key, value) in keys.iter().zip(column.data.chunks_exact_mut(width)) {
+            value.copy_from_slice(&bytes(key)[offset..offset + width]);
+        }
+        if let Some(validity) = column.validity.as_mut() {
+            assert!(validity.len() * 8 >= keys.len(), "the bitmap is too short");
+            for (i, key) in keys.iter().enumerate() {
+                let key = bytes(key);
+                if key[null_offset / 8] & (1 << (null_offset % 8)) == 0 {
+                    validity[i / 8] |= 1 << (i % 8);
+                } else {
+                    validity[i / 8] &= !(1 << (i % 8));
+                }
+            }
+            null_offset += 1;
+        }
+        offset += width;
+    }
+}
@@ -5,6 +5,8 @@
 #![feature(ptr_metadata)]
 #![feature(trivial_bounds)]
 #![feature(let_else)]
+#![feature(generic_associated_types)]
+#![feature(type_alias_impl_trait)]
 #![feature(maybe_uninit_slice)]
 #![feature(once_cell)]
 #![allow(clippy::new_without_default)]
@@ -12,13 +14,29 @@
 #![allow(clippy::len_without_is_empty)]
 #![allow(clippy::type_complexity)]
 
+pub mod adaptive_hashset;
+pub mod aggregate;
 pub mod allocator;
+pub mod arena;
+pub mod bloom_filter;
 pub mod container;
 pub mod hash;
+pub mod key_packing;
+pub mod row_key;
+pub mod string_column;
 pub mod traits;
 
+pub mod concurrent_hashtable;
+pub mod dictionary;
+pub mod frozen_table;
+pub mod group_index;
+pub mod hash_method;
+pub mod hashset;
 pub mod hashtable;
+pub mod multimap;
+pub mod place_hashtable;
 pub mod twolevel_hashtable;
+pub mod twolevel_unsized_hashtable;
 pub mod unsized_hashtable;
 
 mod simd;
@@ -0,0 +1,279 @@
+use crate::hashtable::Hashtable;
+use crate::traits::{Key, UnsizedKey};
+use crate::unsized_hashtable::UnsizedHashtable;
+use std::alloc::Allocator;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const NIL: u32 = u32::MAX;
+
+#[derive(Clone, Copy)]
+pub(crate) struct Link {
+    pub(crate) row: u32,
+    pub(crate) next: u32,
+}
+
+/// Rows of a key are linked from the newest to the oldest.
+pub(crate) struct Chains<A: Allocator + Clone> {
+    pub(crate) links: Vec<Link, A>,
+    /// The `i`-th bit is set if the `i`-th link is matched.
+    pub(crate) matched: Option<Vec<AtomicU64, A>>,
+}
+
+impl<A: Allocator + Clone> Chains<A> {
+    fn new_in(allocator: A) -> Self {
+        Self {
+            links: Vec::new_in(allocator),
+            matched: None,
+        }
+    }
+    fn enable_match_tracking(&mut self) {
+        if self.matched.is_none() {
+            let mut matched = Vec::new_in(self.links.allocator().clone());
+            matched.resize_with((self.links.len() + 63) / 64, || AtomicU64::new(0));
+            self.matched = Some(matched);
+        }
+    }
+    #[inline(always)]
+    fn push(&mut self, head: Result<&mut MaybeUninit<u32>, &mut u32>, row: u32) {
+        let index = self.links.len() as u32;
+        assert_ne!(index, NIL, "the multimap overflows");
+        if let Some(matched) = self.matched.as_mut() {
+            if index % 64 == 0 {
+                matched.push(AtomicU64::new(0));
+            }
+        }
+        match head {
+            Ok(head) => {
+                head.write(index);
+                self.links.push(Link { row, next: NIL });
+            }
+            Err(head) => {
+                self.links.push(Link { row, next: *head });
+                *head = index;
+            }
+        }
+    }
+    #[inline(always)]
+    fn iter(&self, head: Option<u32>) -> impl Iterator<Item = u32> + '_ {
+        let mut cursor = head.unwrap_or(NIL);
+        std::iter::from_fn(move || {
+            if cursor == NIL {
+                return None;
+            }
+            let link = self.links[cursor as usize];
+            cursor = link.next;
+            Some(link.row)
+        })
+    }
+    #[inline(always)]
+    fn probe(&self, head: Option<u32>, mut f: impl FnMut(u32)) {
+        let mut cursor = head.unwrap_or(NIL);
+        while cursor != NIL {
+            let link = self.links[cursor as usize];
+            if let Some(matched) = self.matched.as_ref() {
+                let bit = 1u64 << (cursor % 64);
+                let word = &matched[cursor as usize / 64];
+                if word.load(Ordering::Relaxed) & bit == 0 {
+                    word.fetch_or(bit, Ordering::Relaxed);
+                }
+            }
+            f(link.row);
+            cursor = link.next;
+        }
+    }
+    fn iter_by_matched(&self, expected: bool) -> impl Iterator<Item = u32> + '_ {
+        let matched = self
+            .matched
+            .as_ref()
+            .expect("match tracking is not enabled");
+        self.links.iter().enumerate().filter_map(move |(i, link)| {
+            let bits = matched[i / 64].load(Ordering::Relaxed);
+            if (bits & (1u64 << (i % 64)) != 0) == expected {
+                Some(link.row)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// A hash table that maps a key to all build-side rows with the key.
+pub struct HashMultimap<K, A = crate::allocator::Default>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    pub(crate) table: Hashtable<K, u32, A>,
+    pub(crate) chains: Chains<A>,
+}
+
+impl<K, A> HashMultimap<K, A>
+where
+    K: Key,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+}
+
+impl<K, A> HashMultimap<K, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            table: Hashtable::new_in(allocator.clone()),
+            chains: Chains::new_in(allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.rows() == 0
+    }
+    /// Returns the number of distinct keys.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    /// Returns the number of rows.
+    #[inline(always)]
+    pub fn rows(&self) -> usize {
+        self.chains.links.len()
+    }
+    #[inline(always)]
+    pub fn insert(&mut self, key: K, row: u32) {
+        self.chains.push(unsafe { self.table.insert(key) }, row);
+    }
+    /// Inserts `keys[i]` with row `offset + i`.
+    pub fn insert_batch(&mut self, keys: &[K], offset: u32) {
+        self.chains.links.reserve(keys.len());
+        for (i, &key) in keys.iter().enumerate() {
+            self.insert(key, offset + i as u32);
+        }
+    }
+    pub fn get(&self, key: &K) -> impl Iterator<Item = u32> + '_ {
+        self.chains.iter(self.table.get(key).copied())
+    }
+    /// Tracks which rows are matched by `probe`, for outer joins and semi joins.
+    pub fn enable_match_tracking(&mut self) {
+        self.chains.enable_match_tracking();
+    }
+    /// # Panics
+    ///
+    /// Panics if match tracking is not enabled.
+    pub fn iter_matched(&self) -> impl Iterator<Item = u32> + '_ {
+        self.chains.iter_by_matched(true)
+    }
+    /// # Panics
+    ///
+    /// Panics if match tracking is not enabled.
+    pub fn iter_unmatched(&self) -> impl Iterator<Item = u32> + '_ {
+        self.chains.iter_by_matched(false)
+    }
+    /// Appends `(i, row)` for every row matching `keys[i]`.
+    /// Rows are marked as matched if match tracking is enabled.
+    ///
+    /// Returns the number of matches.
+    pub fn probe(&self, keys: &[K], probe_rows: &mut Vec<u32>, build_rows: &mut Vec<u32>) -> usize {
+        let start = build_rows.len();
+        for (i, key) in keys.iter().enumerate() {
+            self.chains.probe(self.table.get(key).copied(), |row| {
+                probe_rows.push(i as u32);
+                build_rows.push(row);
+            });
+        }
+        build_rows.len() - start
+    }
+}
+
+/// A hash table that maps an unsized key to all build-side rows with the key.
+pub struct UnsizedHashMultimap<K, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    pub(crate) table: UnsizedHashtable<K, u32, A>,
+    pub(crate) chains: Chains<A>,
+}
+
+impl<K, A> UnsizedHashMultimap<K, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            table: UnsizedHashtable::new_in(allocator.clone()),
+            chains: Chains::new_in(allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.rows() == 0
+    }
+    /// Returns the number of distinct keys.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    /// Returns the number of rows.
+    #[inline(always)]
+    pub fn rows(&self) -> usize {
+        self.chains.links.len()
+    }
+    #[inline(always)]
+    pub fn insert(&mut self, key: &K, row: u32) {
+        self.chains.push(unsafe { self.table.insert(key) }, row);
+    }
+    /// Inserts `keys[i]` with row `offset + i`.
+    pub fn insert_batch(&mut self, keys: &[&K], offset: u32) {
+        self.chains.links.reserve(keys.len());
+        for (i, &key) in keys.iter().enumerate() {
+            self.insert(key, offset + i as u32);
+        }
+    }
+    pub fn get(&self, key: &K) -> impl Iterator<Item = u32> + '_ {
+        self.chains.iter(self.table.get(key).copied())
+    }
+    /// Tracks which rows are matched by `probe`, for outer joins and semi joins.
+    pub fn enable_match_tracking(&mut self) {
+        self.chains.enable_match_tracking();
+    }
+    /// # Panics
+    ///
+    /// Panics if match tracking is not enabled.
+    pub fn iter_matched(&self) -> impl Iterator<Item = u32> + '_ {
+        self.chains.iter_by_matched(true)
+    }
+    /// # Panics
+    ///
+    /// Panics if match tracking is not enabled.
+    pub fn iter_unmatched(&self) -> impl Iterator<Item = u32> + '_ {
+        self.chains.iter_by_matched(false)
+    }
+    /// Appends `(i, row)` for every row matching `keys[i]`.
+    /// Rows are marked as matched if match tracking is enabled.
+    ///
+    /// Returns the number of matches.
+    pub fn probe(
+        &self,
+        keys: &[&K],
+        probe_rows: &mut Vec<u32>,
+        build_rows: &mut Vec<u32>,
+    ) -> usize {
+        let start = build_rows.len();
+        for (i, &key) in keys.iter().enumerate() {
+            self.chains.probe(self.table.get(key).copied(), |row| {
+                probe_rows.push(i as u32);
+                build_rows.push(row);
+            });
+        }
+        build_rows.len() - start
+    }
+}
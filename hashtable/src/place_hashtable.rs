@@ -0,0 +1,248 @@
+use crate::arena::Arena;
+use crate::hashtable::Hashtable;
+use crate::traits::{Key, UnsizedKey};
+use crate::unsized_hashtable::UnsizedHashtable;
+use crate::utils::OnUnwind;
+use std::alloc::{Allocator, Layout};
+use std::ptr::NonNull;
+
+/// The layout of aggregate states and the way to drop them.
+#[derive(Clone, Copy)]
+pub struct StateLayout {
+    pub layout: Layout,
+    pub drop: Option<unsafe fn(NonNull<u8>)>,
+}
+
+impl StateLayout {
+    pub fn of<T>() -> Self {
+        Self {
+            layout: Layout::new::<T>(),
+            drop: if std::mem::needs_drop::<T>() {
+                Some(drop_state::<T>)
+            } else {
+                None
+            },
+        }
+    }
+}
+
+unsafe fn drop_state<T>(place: NonNull<u8>) {
+    std::ptr::drop_in_place(place.cast::<T>().as_ptr());
+}
+
+pub(crate) struct Places<A: Allocator + Clone> {
+    pub(crate) arena: Arena<A>,
+    pub(crate) state: StateLayout,
+    pub(crate) batch: Vec<NonNull<u8>, A>,
+}
+
+impl<A: Allocator + Clone> Places<A> {
+    fn new_in(state: StateLayout, allocator: A) -> Self {
+        Self {
+            arena: Arena::new_in(allocator.clone()),
+            state,
+            batch: Vec::new_in(allocator),
+        }
+    }
+    #[inline(always)]
+    fn alloc(&mut self) -> NonNull<u8> {
+        self.arena.alloc_layout(self.state.layout)
+    }
+}
+
+/// A hash table whose values are places of aggregate states.
+///
+/// States are allocated in an arena of the table, so their addresses never change.
+pub struct PlaceHashtable<K, A = crate::allocator::Default>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    table: Hashtable<K, NonNull<u8>, A>,
+    places: Places<A>,
+}
+
+impl<K, A> PlaceHashtable<K, A>
+where
+    K: Key,
+    A: Allocator + Clone + Default,
+{
+    pub fn new(state: StateLayout) -> Self {
+        Self::new_in(state, Default::default())
+    }
+}
+
+impl<K, A> PlaceHashtable<K, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    pub fn new_in(state: StateLayout, allocator: A) -> Self {
+        Self {
+            table: Hashtable::new_in(allocator.clone()),
+            places: Places::new_in(state, allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<NonNull<u8>> {
+        self.table.get(key).copied()
+    }
+    /// Returns `Ok` with a new place if the key is newly inserted.
+    ///
+    /// # Safety
+    ///
+    /// The new place should be initialized immedidately.
+    #[inline(always)]
+    pub unsafe fn insert(&mut self, key: K) -> Result<NonNull<u8>, NonNull<u8>> {
+        match self.table.insert(key) {
+            Ok(x) => Ok(*x.write(self.places.alloc())),
+            Err(x) => Err(*x),
+        }
+    }
+    /// Returns places of `keys`. New places are initialized by `init`.
+    ///
+    /// If `init` panics, the key of the place is removed, so the place is never dropped.
+    pub fn insert_batch<F>(&mut self, keys: &[K], mut init: F) -> &[NonNull<u8>]
+    where
+        F: FnMut(NonNull<u8>),
+    {
+        self.places.batch.clear();
+        self.places.batch.reserve(keys.len());
+        for &key in keys.iter() {
+            let place = match unsafe { self.table.insert(key) } {
+                Ok(x) => {
+                    let place = *x.write(self.places.alloc());
+                    let table = &mut self.table;
+                    let guard = OnUnwind(|| unsafe { table.unregister(key) });
+                    init(place);
+                    std::mem::forget(guard);
+                    place
+                }
+                Err(x) => *x,
+            };
+            self.places.batch.push(place);
+        }
+        &self.places.batch
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&K, NonNull<u8>)> {
+        self.table.iter().map(|(key, place)| (key, *place))
+    }
+}
+
+impl<K, A> Drop for PlaceHashtable<K, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        if let Some(drop) = self.places.state.drop {
+            for (_, &place) in self.table.iter() {
+                unsafe { drop(place) }
+            }
+        }
+    }
+}
+
+/// A hash table whose keys are unsized and values are places of aggregate states.
+///
+/// States are allocated in an arena of the table, so their addresses never change.
+pub struct UnsizedPlaceHashtable<K, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    table: UnsizedHashtable<K, NonNull<u8>, A>,
+    places: Places<A>,
+}
+
+impl<K, A> UnsizedPlaceHashtable<K, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone + Default,
+{
+    pub fn new(state: StateLayout) -> Self {
+        Self::new_in(state, Default::default())
+    }
+    pub fn new_in(state: StateLayout, allocator: A) -> Self {
+        Self {
+            table: UnsizedHashtable::new_in(allocator.clone()),
+            places: Places::new_in(state, allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<NonNull<u8>> {
+        self.table.get(key).copied()
+    }
+    /// Returns `Ok` with a new place if the key is newly inserted.
+    ///
+    /// # Safety
+    ///
+    /// The new place should be initialized immedidately.
+    #[inline(always)]
+    pub unsafe fn insert(&mut self, key: &K) -> Result<NonNull<u8>, NonNull<u8>> {
+        match self.table.insert(key) {
+            Ok(x) => Ok(*x.write(self.places.alloc())),
+            Err(x) => Err(*x),
+        }
+    }
+    /// Returns places of `keys`. New places are initialized by `init`.
+    ///
+    /// If `init` panics, the key of the place is removed, so the place is never dropped.
+    pub fn insert_batch<F>(&mut self, keys: &[&K], mut init: F) -> &[NonNull<u8>]
+    where
+        F: FnMut(NonNull<u8>),
+    {
+        self.places.batch.clear();
+        self.places.batch.reserve(keys.len());
+        for &key in keys.iter() {
+            let place = match unsafe { self.table.insert(key) } {
+                Ok(x) => {
+                    let place = *x.write(self.places.alloc());
+                    let table = &mut self.table;
+                    let guard = OnUnwind(|| {
+                        table.remove(key);
+                    });
+                    init(place);
+                    std::mem::forget(guard);
+                    place
+                }
+                Err(x) => *x,
+            };
+            self.places.batch.push(place);
+        }
+        &self.places.batch
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&K, NonNull<u8>)> {
+        self.table.iter().map(|(key, place)| (key, *place))
+    }
+}
+
+impl<K, A> Drop for UnsizedPlaceHashtable<K, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    fn drop(&mut self) {
+        if let Some(drop) = self.places.state.drop {
+            for (_, &place) in self.table.iter() {
+                unsafe { drop(place) }
+            }
+        }
+    }
+}
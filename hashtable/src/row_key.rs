@@ -0,0 +1,190 @@
+use crate::key_packing::Packable;
+use std::alloc::Allocator;
+use std::mem::size_of;
+
+/// Keys ending with zero are stored out of line by `UnsizedHashtable`, so such
+/// keys are followed by this byte.
+const SENTINEL: u8 = 1;
+
+/// Serializes key columns of rows into byte strings for `UnsizedHashtable`.
+///
+/// Fixed-width values are written as they are, variable-length values are prefixed
+/// with LEB128 lengths, and nullable values are prefixed with a flag byte. All keys
+/// are written into one buffer, which is reused after `clear`.
+pub struct RowKeyWriter<A = crate::allocator::Default>
+where
+    A: Allocator + Clone,
+{
+    offsets: Vec<usize, A>,
+    data: Vec<u8, A>,
+}
+
+impl<A> RowKeyWriter<A>
+where
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+}
+
+impl<A> RowKeyWriter<A>
+where
+    A: Allocator + Clone,
+{
+    pub fn new_in(allocator: A) -> Self {
+        let mut offsets = Vec::new_in(allocator.clone());
+        offsets.push(0);
+        Self {
+            offsets,
+            data: Vec::new_in(allocator),
+        }
+    }
+    /// Removes all keys, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.offsets.truncate(1);
+        self.data.clear();
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the number of finished keys.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+    #[inline(always)]
+    pub fn write_fixed<T: Packable>(&mut self, value: T) {
+        let bytes =
+            unsafe { std::slice::from_raw_parts(&value as *const T as *const u8, size_of::<T>()) };
+        self.write_raw(bytes);
+    }
+    #[inline(always)]
+    pub(crate) fn write_raw(&mut self, value: &[u8]) {
+        self.data.extend_from_slice(value);
+    }
+    #[inline(always)]
+    pub(crate) fn write_flag(&mut self, valid: bool) {
+        self.data.push(valid as u8);
+    }
+    #[inline(always)]
+    pub fn write_bytes(&mut self, value: &[u8]) {
+        let mut len = value.len();
+        while len >= 0x80 {
+            self.data.push(len as u8 | 0x80);
+            len >>= 7;
+        }
+        self.data.push(len as u8);
+        self.data.extend_from_slice(value);
+    }
+    #[inline(always)]
+    pub fn write_str(&mut self, value: &str) {
+        self.write_bytes(value.as_bytes());
+    }
+    #[inline(always)]
+    pub fn write_nullable_fixed<T: Packable>(&mut self, value: Option<T>) {
+        self.write_flag(value.is_some());
+        if let Some(value) = value {
+            self.write_fixed(value);
+        }
+    }
+    #[inline(always)]
+    pub fn write_nullable_bytes(&mut self, value: Option<&[u8]>) {
+        self.write_flag(value.is_some());
+        if let Some(value) = value {
+            self.write_bytes(value);
+        }
+    }
+    #[inline(always)]
+    pub fn write_nullable_str(&mut self, value: Option<&str>) {
+        self.write_nullable_bytes(value.map(str::as_bytes));
+    }
+    /// Finishes the current key.
+    #[inline(always)]
+    pub fn finish_row(&mut self) {
+        let start = self.offsets[self.offsets.len() - 1];
+        if self.data.len() > start && self.data.last().copied() == Some(0) {
+            self.data.push(SENTINEL);
+        }
+        self.offsets.push(self.data.len());
+    }
+    /// # Panics
+    ///
+    /// Panics if the key is not finished.
+    #[inline(always)]
+    pub fn key(&self, index: usize) -> &[u8] {
+        &self.data[self.offsets[index]..self.offsets[index + 1]]
+    }
+    pub fn keys(&self) -> impl Iterator<Item = &[u8]> {
+        self.offsets.windows(2).map(|x| &self.data[x[0]..x[1]])
+    }
+}
+
+/// Deserializes a key written by `RowKeyWriter`, whose fields should be read in
+/// the order they're written, one per key column.
+///
+/// Reading methods panic if the key is truncated.
+pub struct RowKeyReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> RowKeyReader<'a> {
+    pub fn new(key: &'a [u8]) -> Self {
+        Self { bytes: key }
+    }
+    #[inline(always)]
+    fn take(&mut self, n: usize) -> &'a [u8] {
+        let (x, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        x
+    }
+    #[inline(always)]
+    pub fn read_fixed<T: Packable>(&mut self) -> T {
+        let x = self.take(size_of::<T>());
+        unsafe { (x.as_ptr() as *const T).read_unaligned() }
+    }
+    #[inline(always)]
+    pub fn read_bytes(&mut self) -> &'a [u8] {
+        let mut len = 0usize;
+        let mut shift = 0;
+        loop {
+            let byte = self.take(1)[0];
+            len |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        self.take(len)
+    }
+    /// # Panics
+    ///
+    /// Panics if the value is not UTF-8.
+    #[inline(always)]
+    pub fn read_str(&mut self) -> &'a str {
+        std::str::from_utf8(self.read_bytes()).unwrap()
+    }
+    #[inline(always)]
+    pub fn read_nullable_fixed<T: Packable>(&mut self) -> Option<T> {
+        match self.take(1)[0] {
+            0 => None,
+            _ => Some(self.read_fixed()),
+        }
+    }
+    #[inline(always)]
+    pub fn read_nullable_bytes(&mut self) -> Option<&'a [u8]> {
+        match self.take(1)[0] {
+            0 => None,
+            _ => Some(self.read_bytes()),
+        }
+    }
+    /// # Panics
+    ///
+    /// Panics if the value is not UTF-8.
+    #[inline(always)]
+    pub fn read_nullable_str(&mut self) -> Option<&'a str> {
+        self.read_nullable_bytes()
+            .map(|x| std::str::from_utf8(x).unwrap())
+    }
+}
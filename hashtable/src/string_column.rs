@@ -0,0 +1,85 @@
+use crate::key_packing::is_valid;
+
+/// Offsets of a `StringColumn`.
+pub trait Offset: Copy {
+    fn as_usize(self) -> usize;
+    /// # Panics
+    ///
+    /// Panics if `x` overflows.
+    fn from_usize(x: usize) -> Self;
+}
+
+impl Offset for u32 {
+    #[inline(always)]
+    fn as_usize(self) -> usize {
+        self as usize
+    }
+    #[inline(always)]
+    fn from_usize(x: usize) -> Self {
+        u32::try_from(x).expect("offsets overflow")
+    }
+}
+
+impl Offset for u64 {
+    #[inline(always)]
+    fn as_usize(self) -> usize {
+        self as usize
+    }
+    #[inline(always)]
+    fn from_usize(x: usize) -> Self {
+        x as u64
+    }
+}
+
+/// A column of variable-length values laid out as Arrow does, whose `i`-th value is
+/// `data[offsets[i]..offsets[i + 1]]`.
+///
+/// Null bitmaps are LSB-first, and a set bit means that the value is valid.
+#[derive(Clone, Copy)]
+pub struct StringColumn<'a, O: Offset = u32> {
+    offsets: &'a [O],
+    data: &'a [u8],
+    validity: Option<&'a [u8]>,
+}
+
+impl<'a, O: Offset> StringColumn<'a, O> {
+    /// # Panics
+    ///
+    /// Panics if `offsets` is empty.
+    pub fn new(offsets: &'a [O], data: &'a [u8]) -> Self {
+        assert!(!offsets.is_empty(), "offsets should not be empty");
+        Self {
+            offsets,
+            data,
+            validity: None,
+        }
+    }
+    pub fn with_validity(self, validity: &'a [u8]) -> Self {
+        Self {
+            validity: Some(validity),
+            ..self
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+    /// Returns `None` if the value is null.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&'a [u8]> {
+        if is_valid(self.validity, index) {
+            let start = self.offsets[index].as_usize();
+            let end = self.offsets[index + 1].as_usize();
+            Some(&self.data[start..end])
+        } else {
+            None
+        }
+    }
+    pub fn iter(&self) -> impl Iterator<Item = Option<&'a [u8]>> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+}
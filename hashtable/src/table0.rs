@@ -48,6 +48,16 @@ where
             dropped: false,
         }
     }
+    /// Creates a table of no slots, which doesn't allocate until the first `grow`.
+    /// Probes miss without extra branches, since the mask of an empty table is zero.
+    pub fn empty_in(allocator: A) -> Self {
+        Self {
+            slots: unsafe { C::new_zeroed(0, allocator.clone()) },
+            len: 0,
+            allocator,
+            dropped: false,
+        }
+    }
     #[inline(always)]
     pub fn len(&self) -> usize {
         self.len
@@ -70,7 +80,7 @@ where
     #[inline(always)]
     pub unsafe fn get_with_hash(&self, key: &K, hash: u64) -> Option<&V> {
         assume(!K::equals_zero(key));
-        let index = (hash as usize) & (self.slots.len() - 1);
+        let index = (hash as usize) & self.slots.len().saturating_sub(1);
         for i in (index..self.slots.len()).chain(0..index) {
             assume(i < self.slots.len());
             if self.slots[i].is_zero() {
@@ -96,7 +106,7 @@ where
     #[inline(always)]
     pub unsafe fn get_with_hash_mut(&mut self, key: &K, hash: u64) -> Option<&mut V> {
         assume(!K::equals_zero(key));
-        let index = (hash as usize) & (self.slots.len() - 1);
+        let index = (hash as usize) & self.slots.len().saturating_sub(1);
         for i in (index..self.slots.len()).chain(0..index) {
             assume(i < self.slots.len());
             if self.slots[i].is_zero() {
@@ -135,7 +145,7 @@ where
         hash: u64,
     ) -> Result<&mut MaybeUninit<V>, &mut V> {
         assume(!K::equals_zero(&key));
-        let index = (hash as usize) & (self.slots.len() - 1);
+        let index = (hash as usize) & self.slots.len().saturating_sub(1);
         for i in (index..self.slots.len()).chain(0..index) {
             assume(i < self.slots.len());
             if self.slots[i].is_zero() {
@@ -149,6 +159,163 @@ where
         }
         panic!("the hash table overflows")
     }
+    /// Same as `insert_with_hash`, but returns the whole slot if the key is newly inserted,
+    /// so that the key could be replaced by an equal one.
+    ///
+    /// # Safety
+    ///
+    /// `key` doesn't equal to zero.
+    /// The value of the resulted slot should be initialized immedidately.
+    /// Provided hash is correct.
+    ///
+    /// # Panics
+    /// The hashtable is full.
+    #[inline(always)]
+    pub(crate) unsafe fn insert_slot_with_hash(
+        &mut self,
+        key: K,
+        hash: u64,
+    ) -> Result<&mut Slot<K, V>, &mut V> {
+        assume(!K::equals_zero(&key));
+        let index = (hash as usize) & self.slots.len().saturating_sub(1);
+        for i in (index..self.slots.len()).chain(0..index) {
+            assume(i < self.slots.len());
+            if self.slots[i].is_zero() {
+                self.len += 1;
+                self.slots[i].key.write(key);
+                return Ok(&mut self.slots[i]);
+            }
+            if self.slots[i].key.assume_init_ref() == &key {
+                return Err(self.slots[i].val.assume_init_mut());
+            }
+        }
+        panic!("the hash table overflows")
+    }
+    /// # Safety
+    ///
+    /// `key` doesn't equal to zero.
+    /// Provided hash is correct.
+    pub unsafe fn remove_with_hash(&mut self, key: &K, hash: u64) -> Option<(K, V)> {
+        let slot = self.remove_slot_with_hash(key, hash)?;
+        Some((slot.key.assume_init(), slot.val.assume_init()))
+    }
+    /// Same as `remove_with_hash`, but returns the whole slot, so that the value is not read
+    /// if it's not initialized.
+    ///
+    /// # Safety
+    ///
+    /// `key` doesn't equal to zero.
+    /// Provided hash is correct.
+    pub(crate) unsafe fn remove_slot_with_hash(
+        &mut self,
+        key: &K,
+        hash: u64,
+    ) -> Option<Slot<K, V>> {
+        assume(!K::equals_zero(key));
+        if self.slots.is_empty() {
+            return None;
+        }
+        let mask = self.slots.len() - 1;
+        let mut i = (hash as usize) & mask;
+        loop {
+            assume(i < self.slots.len());
+            if self.slots[i].is_zero() {
+                return None;
+            }
+            if self.slots[i].key.assume_init_ref() == key {
+                break;
+            }
+            i = (i + 1) & mask;
+        }
+        let slot = std::ptr::read(&self.slots[i]);
+        self.slots[i].key = MaybeUninit::zeroed();
+        self.len -= 1;
+        let mut j = i;
+        loop {
+            j = (j + 1) & mask;
+            assume(j < self.slots.len());
+            if self.slots[j].is_zero() {
+                break;
+            }
+            let k = (K::hash(self.slots[j].key.assume_init_ref()) as usize) & mask;
+            let stays = if i <= j {
+                i < k && k <= j
+            } else {
+                i < k || k <= j
+            };
+            if !stays {
+                self.slots[i] = std::ptr::read(&self.slots[j]);
+                self.slots[j].key = MaybeUninit::zeroed();
+                i = j;
+            }
+        }
+        Some(slot)
+    }
+    /// Removes all entries that `f` returns `false` for, and calls `g` with removed keys.
+    pub fn retain<F, G>(&mut self, mut f: F, mut g: G)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+        G: FnMut(&K),
+    {
+        for i in 0..self.slots.len() {
+            unsafe {
+                assume(i < self.slots.len());
+            }
+            if K::is_zero(&self.slots[i].key) {
+                continue;
+            }
+            let slot = &mut self.slots[i];
+            let keep = unsafe { f(slot.key.assume_init_ref(), slot.val.assume_init_mut()) };
+            if !keep {
+                unsafe {
+                    g(slot.key.assume_init_ref());
+                    slot.val.assume_init_drop();
+                }
+                slot.key = MaybeUninit::zeroed();
+                self.len -= 1;
+            }
+        }
+        for i in 0..self.slots.len() {
+            unsafe {
+                assume(i < self.slots.len());
+            }
+            if K::is_zero(&self.slots[i].key) {
+                continue;
+            }
+            self.relocate(i);
+        }
+        for i in 0..self.slots.len() {
+            unsafe {
+                assume(i < self.slots.len());
+            }
+            if K::is_zero(&self.slots[i].key) {
+                break;
+            }
+            self.relocate(i);
+        }
+    }
+    /// Moves the `i`-th slot to the first vacant slot of its probe sequence.
+    #[inline(always)]
+    fn relocate(&mut self, i: usize) {
+        let key = unsafe { self.slots[i].key.assume_init_ref() };
+        let hash = K::hash(key);
+        let index = (hash as usize) & (self.slots.len() - 1);
+        for j in (index..self.slots.len()).chain(0..index) {
+            unsafe {
+                assume(j < self.slots.len());
+            }
+            if j == i {
+                break;
+            }
+            if self.slots[j].is_zero() {
+                unsafe {
+                    self.slots[j] = std::ptr::read(&self.slots[i]);
+                    self.slots[i].key = MaybeUninit::zeroed();
+                }
+                break;
+            }
+        }
+    }
     pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
         self.slots
             .iter()
@@ -176,7 +343,27 @@ where
             f(key, result, slot.val.assume_init_read());
         }
     }
+    /// Rebuilds the table with the smallest capacity that keeps the load factor below `1/2`.
+    pub fn shrink_to_fit(&mut self) {
+        let mut other = Self::with_capacity_in(self.len * 2, self.allocator.clone());
+        if other.capacity() >= self.capacity() {
+            return;
+        }
+        unsafe {
+            for slot in self.iter_raw_mut() {
+                let key = slot.key.assume_init_read();
+                let val = slot.val.assume_init_read();
+                other.insert(key).ok().unwrap().write(val);
+            }
+        }
+        self.dropped = true;
+        *self = other;
+    }
     pub fn grow(&mut self, shift: u8) {
+        if self.slots.is_empty() {
+            self.slots = unsafe { C::new_zeroed(8, self.allocator.clone()) };
+            return;
+        }
         let old_capacity = self.slots.len();
         let new_capacity = self.slots.len() << shift;
         unsafe {
@@ -299,6 +486,58 @@ where
     }
 }
 
+pub(crate) struct Table0IntoIter<K, V, C, A>
+where
+    K: Key,
+    C: Container<T = Slot<K, V>, A = A>,
+    A: Allocator + Clone,
+{
+    table: Table0<K, V, C, A>,
+    index: usize,
+}
+
+impl<K, V, C, A> Table0IntoIter<K, V, C, A>
+where
+    K: Key,
+    C: Container<T = Slot<K, V>, A = A>,
+    A: Allocator + Clone,
+{
+    pub(crate) fn new(table: Table0<K, V, C, A>) -> Self {
+        Self { table, index: 0 }
+    }
+}
+
+impl<K, V, C, A> Iterator for Table0IntoIter<K, V, C, A>
+where
+    K: Key,
+    C: Container<T = Slot<K, V>, A = A>,
+    A: Allocator + Clone,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.table.slots.len() {
+            let slot = &mut self.table.slots[self.index];
+            self.index += 1;
+            if slot.is_zero() {
+                continue;
+            }
+            unsafe {
+                let key = slot.key.assume_init_read();
+                let val = slot.val.assume_init_read();
+                slot.key = MaybeUninit::zeroed();
+                self.table.len -= 1;
+                return Some((key, val));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.table.len(), Some(self.table.len()))
+    }
+}
+
 impl<K, V, C, A> Drop for Table0<K, V, C, A>
 where
     K: Key,
@@ -73,6 +73,57 @@ impl<V, A: Allocator + Clone> Table1<V, A> {
             Ok(&mut self.inner.data[x][y as usize])
         }
     }
+    pub fn remove(&mut self, key: [u8; 2]) -> Option<V> {
+        let x = ((key[0] as usize) << 2) | (key[1] as usize >> 6);
+        let y = key[1] & 63;
+        let z = (self.inner.bits[x] & (1 << y)) != 0;
+        if z {
+            self.len -= 1;
+            self.inner.bits[x] ^= 1 << y;
+            Some(unsafe { self.inner.data[x][y as usize].assume_init_read() })
+        } else {
+            None
+        }
+    }
+    /// Removes all entries that `f` returns `false` for.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&[u8; 2], &mut V) -> bool,
+    {
+        for x in 0..1024 {
+            let mut bits = self.inner.bits[x];
+            while bits != 0 {
+                let y = bits.trailing_zeros() as usize;
+                bits ^= 1 << y;
+                let key = &ALLKEYS[x >> 2][((x & 3) << 6) | y];
+                let val = unsafe { self.inner.data[x][y].assume_init_mut() };
+                if !f(key, val) {
+                    self.len -= 1;
+                    self.inner.bits[x] ^= 1 << y;
+                    unsafe { self.inner.data[x][y].assume_init_drop() };
+                }
+            }
+        }
+    }
+    /// # Safety
+    ///
+    /// `f` should initialize the resulted `MaybeUninit` immedidately.
+    pub unsafe fn merge<F>(&mut self, mut other: Self, mut f: F)
+    where
+        F: FnMut([u8; 2], Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        for x in 0..1024 {
+            let mut bits = std::mem::take(&mut other.inner.bits[x]);
+            while bits != 0 {
+                let y = bits.trailing_zeros() as usize;
+                bits ^= 1 << y;
+                let key = ALLKEYS[x >> 2][((x & 3) << 6) | y];
+                let val = other.inner.data[x][y].assume_init_read();
+                f(key, self.insert(key), val);
+            }
+        }
+        other.len = 0;
+    }
     pub fn iter(&self) -> impl Iterator<Item = (&[u8; 2], &V)> + '_ {
         self.inner.data.iter().enumerate().flat_map(|(x, group)| {
             let mut bits = self.inner.bits[x];
@@ -122,3 +173,40 @@ impl<V, A: Allocator + Clone> Drop for Table1<V, A> {
         }
     }
 }
+
+pub(crate) struct Table1IntoIter<V, A: Allocator + Clone> {
+    table: Table1<V, A>,
+    index: usize,
+}
+
+impl<V, A: Allocator + Clone> Table1IntoIter<V, A> {
+    pub(crate) fn new(table: Table1<V, A>) -> Self {
+        Self { table, index: 0 }
+    }
+}
+
+impl<V, A: Allocator + Clone> Iterator for Table1IntoIter<V, A> {
+    type Item = ([u8; 2], V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < 1024 {
+            let x = self.index;
+            let bits = self.table.inner.bits[x];
+            if bits == 0 {
+                self.index += 1;
+                continue;
+            }
+            let y = bits.trailing_zeros() as usize;
+            self.table.inner.bits[x] ^= 1 << y;
+            self.table.len -= 1;
+            let key = ALLKEYS[x >> 2][((x & 3) << 6) | y];
+            let val = unsafe { self.table.inner.data[x][y].assume_init_read() };
+            return Some((key, val));
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.table.len(), Some(self.table.len()))
+    }
+}
@@ -1,3 +1,5 @@
+use crate::aggregate::AggregateFunction;
+use primitive_types::U256;
 use std::alloc::Allocator;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::Hasher;
@@ -51,6 +53,24 @@ impl_key_for_primitive_types!(u32);
 impl_key_for_primitive_types!(i32);
 impl_key_for_primitive_types!(u64);
 impl_key_for_primitive_types!(i64);
+impl_key_for_primitive_types!(u128);
+
+unsafe impl Key for U256 {
+    #[inline(always)]
+    fn equals_zero(this: &Self) -> bool {
+        this.is_zero()
+    }
+
+    #[inline(always)]
+    fn is_zero(this: &MaybeUninit<Self>) -> bool {
+        unsafe { this.assume_init_ref().is_zero() }
+    }
+
+    #[inline(always)]
+    fn hash(&self) -> u64 {
+        self.fast_hash()
+    }
+}
 
 impl UnsizedKey for [u8] {
     fn as_bytes(&self) -> &[u8] {
@@ -72,6 +92,88 @@ impl UnsizedKey for str {
     }
 }
 
+/// Operations shared by all hash tables, so that operators could be written once.
+///
+/// Keys are always passed by reference.
+pub trait HashtableLike {
+    type Key: ?Sized;
+    type Value;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, key: &Self::Key) -> Option<&Self::Value>;
+
+    fn get_mut(&mut self, key: &Self::Key) -> Option<&mut Self::Value>;
+
+    /// # Safety
+    ///
+    /// The resulted `MaybeUninit` should be initialized immedidately.
+    unsafe fn insert(
+        &mut self,
+        key: &Self::Key,
+    ) -> Result<&mut MaybeUninit<Self::Value>, &mut Self::Value>;
+
+    /// Moves all entries of `other` into `self`, and calls `f` with keys, insertion results
+    /// and values of `other`.
+    ///
+    /// # Safety
+    ///
+    /// `f` should initialize the resulted `MaybeUninit` immedidately.
+    unsafe fn merge<F>(&mut self, other: Self, f: F)
+    where
+        Self: Sized,
+        F: FnMut(&Self::Key, Result<&mut MaybeUninit<Self::Value>, &mut Self::Value>, Self::Value);
+
+    type Iter<'a>: Iterator<Item = (&'a Self::Key, &'a Self::Value)>
+    where
+        Self: 'a;
+
+    type IterMut<'a>: Iterator<Item = (&'a Self::Key, &'a mut Self::Value)>
+    where
+        Self: 'a;
+
+    fn iter(&self) -> Self::Iter<'_>;
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_>;
+
+    /// Updates states of `keys` by `function` with `args`, which are zipped with `keys`.
+    fn aggregate<'a, 'b, F, I, J>(&mut self, function: &F, keys: J, args: I)
+    where
+        Self::Key: 'b,
+        F: AggregateFunction<'a, State = Self::Value>,
+        I: IntoIterator<Item = F::Arg>,
+        J: IntoIterator<Item = &'b Self::Key>,
+    {
+        for (key, arg) in keys.into_iter().zip(args) {
+            let state = match unsafe { self.insert(key) } {
+                Ok(x) => x.write(function.init()),
+                Err(x) => x,
+            };
+            function.update(state, arg);
+        }
+    }
+
+    /// Moves all states of `other` into `self`, merging states of the same keys by `function`.
+    fn merge_aggregate<'a, F>(&mut self, other: Self, function: &F)
+    where
+        Self: Sized,
+        F: AggregateFunction<'a, State = Self::Value>,
+    {
+        unsafe {
+            self.merge(other, |_, result, state| match result {
+                Ok(x) => {
+                    x.write(state);
+                }
+                Err(x) => function.merge(x, state),
+            });
+        }
+    }
+}
+
 pub unsafe trait Container
 where
     Self: Deref<Target = [Self::T]> + DerefMut,
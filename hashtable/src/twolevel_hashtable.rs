@@ -1,20 +1,51 @@
-use crate::container::HeapContainer;
-use crate::table0::{Slot, Table0};
-use crate::traits::Key;
+use crate::hashtable::{self, Hashtable};
+use crate::traits::{HashtableLike, Key};
 use std::alloc::Allocator;
 use std::intrinsics::unlikely;
 use std::mem::MaybeUninit;
 
-const BUCKETS: usize = 256;
-const BUCKETS_LG2: u32 = 8;
+pub(crate) const BUCKETS: usize = 256;
+pub(crate) const BUCKETS_LG2: u32 = 8;
+
+#[inline(always)]
+pub(crate) fn dispatch(hash: u64) -> usize {
+    hash as usize >> (64u32 - BUCKETS_LG2)
+}
+
+/// # Safety
+///
+/// The resulted `MaybeUninit` should be initialized immedidately.
+/// Provided hash is correct.
+#[inline(always)]
+pub(crate) unsafe fn insert_into_bucket<K, V, A>(
+    bucket: &mut Hashtable<K, V, A>,
+    key: K,
+    hash: u64,
+) -> Result<&mut MaybeUninit<V>, &mut V>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    if unlikely(K::equals_zero(&key)) {
+        return bucket.insert(key);
+    }
+    let table = &mut bucket.table;
+    if unlikely((table.len() + 1) * 2 > table.capacity()) {
+        if (table.slots.len() >> 14) == 0 {
+            table.grow(2);
+        } else {
+            table.grow(1);
+        }
+    }
+    table.insert_with_hash(key, hash)
+}
 
 pub struct TwolevelHashtable<K, V, A = crate::allocator::Default>
 where
     K: Key,
     A: Allocator + Clone,
 {
-    zero: Option<Slot<K, V>>,
-    tables: [Table0<K, V, HeapContainer<Slot<K, V>, A>, A>; BUCKETS],
+    tables: [Hashtable<K, V, A>; BUCKETS],
 }
 
 impl<K, V, A> TwolevelHashtable<K, V, A>
@@ -32,10 +63,10 @@ where
     K: Key,
     A: Allocator + Clone,
 {
+    /// Buckets are allocated on their first insertions.
     pub fn new_in(allocator: A) -> Self {
         Self {
-            zero: None,
-            tables: std::array::from_fn(|_| Table0::with_capacity_in(256, allocator.clone())),
+            tables: std::array::from_fn(|_| Hashtable::empty_in(allocator.clone())),
         }
     }
     #[inline(always)]
@@ -44,82 +75,180 @@ where
     }
     #[inline(always)]
     pub fn len(&self) -> usize {
-        self.zero.is_some() as usize + self.tables.iter().map(|x| x.len()).sum::<usize>()
+        self.tables.iter().map(|x| x.len()).sum::<usize>()
     }
     #[inline(always)]
     pub fn capacity(&self) -> usize {
-        self.zero.is_some() as usize + self.tables.iter().map(|x| x.capacity()).sum::<usize>()
+        self.tables.iter().map(|x| x.capacity()).sum::<usize>()
+    }
+    #[inline(always)]
+    pub fn bucket_count(&self) -> usize {
+        BUCKETS
+    }
+    /// Keys are dispatched to buckets by the highest bits of their hashes.
+    #[inline(always)]
+    pub fn bucket(&self, index: usize) -> &Hashtable<K, V, A> {
+        &self.tables[index]
+    }
+    /// # Safety
+    ///
+    /// Keys inserted into the bucket should be dispatched to it.
+    #[inline(always)]
+    pub unsafe fn bucket_mut(&mut self, index: usize) -> &mut Hashtable<K, V, A> {
+        &mut self.tables[index]
+    }
+    pub(crate) fn from_buckets(tables: [Hashtable<K, V, A>; BUCKETS]) -> Self {
+        Self { tables }
+    }
+    pub fn into_buckets(self) -> [Hashtable<K, V, A>; BUCKETS] {
+        self.tables
     }
     #[inline(always)]
     pub fn get(&self, key: &K) -> Option<&V> {
+        let hash = K::hash(key);
+        let index = dispatch(hash);
         if unlikely(K::equals_zero(key)) {
-            if let Some(slot) = self.zero.as_ref() {
-                return Some(unsafe { slot.val.assume_init_ref() });
-            } else {
-                return None;
-            }
+            return self.tables[index].get(key);
         }
-        let hash = K::hash(key);
-        let index = hash as usize >> (64u32 - BUCKETS_LG2);
-        unsafe { self.tables[index].get_with_hash(key, hash) }
+        unsafe { self.tables[index].table.get_with_hash(key, hash) }
     }
     #[inline(always)]
     pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let hash = K::hash(key);
+        let index = dispatch(hash);
         if unlikely(K::equals_zero(key)) {
-            if let Some(slot) = self.zero.as_mut() {
-                return Some(unsafe { slot.val.assume_init_mut() });
-            } else {
-                return None;
-            }
+            return self.tables[index].get_mut(key);
         }
-        let hash = K::hash(key);
-        let index = hash as usize >> (64u32 - BUCKETS_LG2);
-        unsafe { self.tables[index].get_with_hash_mut(key, hash) }
+        unsafe { self.tables[index].table.get_with_hash_mut(key, hash) }
     }
     #[inline(always)]
     pub unsafe fn insert(&mut self, key: K) -> Result<&mut MaybeUninit<V>, &mut V> {
-        if unlikely(K::equals_zero(&key)) {
-            let zero = &mut self.zero;
-            if let Some(slot) = zero {
-                return Err(slot.val.assume_init_mut());
-            } else {
-                *zero = Some(MaybeUninit::zeroed().assume_init());
-                return Ok(&mut zero.as_mut().unwrap().val);
-            }
-        }
         let hash = K::hash(&key);
-        let index = hash as usize >> (64u32 - BUCKETS_LG2);
-        if unlikely((self.tables[index].len() + 1) * 2 > self.tables[index].capacity()) {
-            if (self.tables[index].slots.len() >> 14) == 0 {
-                self.tables[index].grow(2);
-            } else {
-                self.tables[index].grow(1);
-            }
-        }
-        self.tables[index].insert_with_hash(key, hash)
+        insert_into_bucket(&mut self.tables[dispatch(hash)], key, hash)
     }
     #[inline(always)]
     pub unsafe fn merge<F>(&mut self, other: Self, mut f: F)
     where
         F: FnMut(K, Result<&mut MaybeUninit<V>, &mut V>, V),
     {
-        if let Some(Slot { key, val, .. }) = other.zero {
-            let key = key.assume_init();
-            let val = val.assume_init();
-            f(key, self.insert(key), val);
-        }
         for (i, table) in other.tables.into_iter().enumerate() {
-            while (self.tables[i].len() + table.len()) * 2 > self.tables[i].capacity() {
-                if (self.tables[i].slots.len() >> 22) == 0 {
-                    self.tables[i].grow(2);
-                } else {
-                    self.tables[i].grow(1);
-                }
-            }
             self.tables[i].merge(table, &mut f);
         }
     }
     pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
         self.tables.iter().flat_map(|x| x.iter())
     }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.tables.iter_mut().flat_map(|x| x.iter_mut())
+    }
+}
+
+impl<K, V, A> HashtableLike for TwolevelHashtable<K, V, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    type Key = K;
+    type Value = V;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+
+    unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        self.insert(*key)
+    }
+
+    unsafe fn merge<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        self.merge(other, |key, result, value| f(&key, result, value))
+    }
+
+    type Iter<'a>
+        = impl Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a;
+
+    type IterMut<'a>
+        = impl Iterator<Item = (&'a K, &'a mut V)>
+    where
+        Self: 'a;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.iter_mut()
+    }
+}
+
+/// Buckets are dropped as soon as all their entries are emitted.
+pub struct IntoIter<K, V, A = crate::allocator::Default>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    current: Option<hashtable::IntoIter<K, V, A>>,
+    tables: std::array::IntoIter<Hashtable<K, V, A>, BUCKETS>,
+    len: usize,
+}
+
+impl<K, V, A> Iterator for IntoIter<K, V, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.current.as_mut()?.next() {
+                self.len -= 1;
+                return Some(item);
+            }
+            self.current = self.tables.next().map(IntoIterator::into_iter);
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V, A> ExactSizeIterator for IntoIter<K, V, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+}
+
+impl<K, V, A> IntoIterator for TwolevelHashtable<K, V, A>
+where
+    K: Key,
+    A: Allocator + Clone,
+{
+    type Item = (K, V);
+
+    type IntoIter = IntoIter<K, V, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len();
+        let mut tables = self.tables.into_iter();
+        IntoIter {
+            current: tables.next().map(IntoIterator::into_iter),
+            tables,
+            len,
+        }
+    }
 }
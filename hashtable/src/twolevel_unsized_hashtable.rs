@@ -0,0 +1,515 @@
+use crate::table1::{Table1, Table1IntoIter};
+use crate::traits::{HashtableLike, UnsizedKey};
+use crate::twolevel_hashtable::{dispatch, BUCKETS};
+use crate::unsized_hashtable::{boxed, short_key_bytes, Prepared, Tiers, TiersIntoIter};
+use std::alloc::Allocator;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+
+/// Shards the length tiers of `UnsizedHashtable` for keys longer than two bytes across buckets,
+/// each of which owns an arena for its long keys. Keys of at most two bytes share one bucket.
+pub struct TwolevelUnsizedHashtable<K, V, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    short: UnsizedShortBucket<K, V, A>,
+    tables: [UnsizedBucket<K, V, A>; BUCKETS],
+}
+
+impl<K, V, A> TwolevelUnsizedHashtable<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+}
+
+impl<K, V, A> TwolevelUnsizedHashtable<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    /// Buckets are allocated on their first insertions.
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            short: UnsizedShortBucket::new_in(allocator.clone()),
+            tables: std::array::from_fn(|_| UnsizedBucket::new_in(allocator.clone())),
+        }
+    }
+    /// # Safety
+    ///
+    /// Buckets should come from `into_buckets`, and keys inserted into a bucket since then
+    /// should be dispatched to it.
+    pub unsafe fn from_buckets(
+        short: UnsizedShortBucket<K, V, A>,
+        tables: [UnsizedBucket<K, V, A>; BUCKETS],
+    ) -> Self {
+        Self { short, tables }
+    }
+    /// Splits the table into the bucket of keys of at most two bytes and the other buckets,
+    /// so that buckets could be merged independently.
+    pub fn into_buckets(
+        self,
+    ) -> (
+        UnsizedShortBucket<K, V, A>,
+        [UnsizedBucket<K, V, A>; BUCKETS],
+    ) {
+        (self.short, self.tables)
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.short.len() + self.tables.iter().map(|x| x.len()).sum::<usize>()
+    }
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.short.capacity() + self.tables.iter().map(|x| x.capacity()).sum::<usize>()
+    }
+    #[inline(always)]
+    pub fn bucket_count(&self) -> usize {
+        BUCKETS
+    }
+    /// Keys longer than two bytes are dispatched to buckets by the highest bits of
+    /// `unsized_hashtable::key_hash`.
+    #[inline(always)]
+    pub fn bucket(&self, index: usize) -> &UnsizedBucket<K, V, A> {
+        &self.tables[index]
+    }
+    /// # Safety
+    ///
+    /// Keys inserted into the bucket should be dispatched to it.
+    #[inline(always)]
+    pub unsafe fn bucket_mut(&mut self, index: usize) -> &mut UnsizedBucket<K, V, A> {
+        &mut self.tables[index]
+    }
+    #[inline(always)]
+    pub fn short_bucket(&self) -> &UnsizedShortBucket<K, V, A> {
+        &self.short
+    }
+    #[inline(always)]
+    pub fn short_bucket_mut(&mut self) -> &mut UnsizedShortBucket<K, V, A> {
+        &mut self.short
+    }
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        match Prepared::new(key.as_bytes()) {
+            Prepared::Short(key) => self.short.get_short(key),
+            key => unsafe { self.tables[dispatch(key.hash())].tiers.get(key) },
+        }
+    }
+    #[inline(always)]
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        match Prepared::new(key.as_bytes()) {
+            Prepared::Short(key) => self.short.get_short_mut(key),
+            key => unsafe { self.tables[dispatch(key.hash())].tiers.get_mut(key) },
+        }
+    }
+    #[inline(always)]
+    pub unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        match Prepared::new(key.as_bytes()) {
+            Prepared::Short(key) => self.short.insert_short(key),
+            key => self.tables[dispatch(key.hash())].tiers.insert::<true>(key),
+        }
+    }
+    /// Merges buckets of `other` into buckets of `self` one by one, and each bucket takes
+    /// over the arena of its counterpart.
+    ///
+    /// # Safety
+    ///
+    /// `f` should initialize the resulted `MaybeUninit` immedidately.
+    pub unsafe fn merge<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        let Self { short, tables } = other;
+        self.short.merge(short, &mut f);
+        for (i, table) in tables.into_iter().enumerate() {
+            self.tables[i].merge(table, &mut f);
+        }
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.tables
+            .iter()
+            .flat_map(|x| x.iter())
+            .chain(self.short.iter())
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.tables
+            .iter_mut()
+            .flat_map(|x| x.iter_mut())
+            .chain(self.short.iter_mut())
+    }
+}
+
+impl<K, V, A> HashtableLike for TwolevelUnsizedHashtable<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Key = K;
+    type Value = V;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+
+    unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        self.insert(key)
+    }
+
+    unsafe fn merge<F>(&mut self, other: Self, f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        self.merge(other, f)
+    }
+
+    type Iter<'a>
+        = impl Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a;
+
+    type IterMut<'a>
+        = impl Iterator<Item = (&'a K, &'a mut V)>
+    where
+        Self: 'a;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.iter_mut()
+    }
+}
+
+/// A bucket of `TwolevelUnsizedHashtable`, holding the length tiers of keys longer than two
+/// bytes.
+pub struct UnsizedBucket<K, V, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    tiers: Tiers<K, V, A>,
+}
+
+impl<K, V, A> UnsizedBucket<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    fn new_in(allocator: A) -> Self {
+        Self {
+            tiers: Tiers::empty_in(allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.tiers.len()
+    }
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.tiers.capacity()
+    }
+    /// Keys of at most two bytes are never found in buckets.
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        unsafe { self.tiers.get(Prepared::new(key.as_bytes())) }
+    }
+    #[inline(always)]
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        unsafe { self.tiers.get_mut(Prepared::new(key.as_bytes())) }
+    }
+    /// # Safety
+    ///
+    /// The resulted `MaybeUninit` should be initialized immedidately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` has at most two bytes.
+    #[inline(always)]
+    pub unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        self.tiers.insert::<true>(Prepared::new(key.as_bytes()))
+    }
+    /// Moves all entries of `other` into `self`, and calls `f` with keys, insertion results
+    /// and values of `other`. Long keys of `other` are not copied, since `self` takes over
+    /// its arena.
+    ///
+    /// # Safety
+    ///
+    /// `f` should initialize the resulted `MaybeUninit` immedidately.
+    pub unsafe fn merge<F>(&mut self, other: Self, f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        self.tiers.merge(other.tiers, f);
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.tiers.iter()
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.tiers.iter_mut()
+    }
+}
+
+pub struct BucketIntoIter<K, V, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    tiers: TiersIntoIter<K, V, A>,
+}
+
+impl<K, V, A> Iterator for BucketIntoIter<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Item = (Box<K>, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tiers.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.tiers.size_hint()
+    }
+}
+
+impl<K, V, A> IntoIterator for UnsizedBucket<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Item = (Box<K>, V);
+
+    type IntoIter = BucketIntoIter<K, V, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        BucketIntoIter {
+            tiers: self.tiers.into_iter(),
+        }
+    }
+}
+
+/// The bucket of `TwolevelUnsizedHashtable` holding keys of at most two bytes, whose table
+/// is allocated on the first insertion.
+pub struct UnsizedShortBucket<K, V, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    allocator: A,
+    table: Option<Table1<V, A>>,
+    _phantom: PhantomData<K>,
+}
+
+impl<K, V, A> UnsizedShortBucket<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    fn new_in(allocator: A) -> Self {
+        Self {
+            allocator,
+            table: None,
+            _phantom: PhantomData,
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.as_ref().map_or(0, |x| x.len())
+    }
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.table.as_ref().map_or(0, |x| x.capacity())
+    }
+    /// Keys longer than two bytes are never found in this bucket.
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        match Prepared::new(key.as_bytes()) {
+            Prepared::Short(key) => self.get_short(key),
+            _ => None,
+        }
+    }
+    #[inline(always)]
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        match Prepared::new(key.as_bytes()) {
+            Prepared::Short(key) => self.get_short_mut(key),
+            _ => None,
+        }
+    }
+    /// # Safety
+    ///
+    /// The resulted `MaybeUninit` should be initialized immedidately.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` has more than two bytes.
+    #[inline(always)]
+    pub unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        match Prepared::new(key.as_bytes()) {
+            Prepared::Short(key) => self.insert_short(key),
+            _ => panic!("keys of more than two bytes are not stored in the short bucket"),
+        }
+    }
+    #[inline(always)]
+    fn get_short(&self, key: [u8; 2]) -> Option<&V> {
+        self.table.as_ref()?.get(key)
+    }
+    #[inline(always)]
+    fn get_short_mut(&mut self, key: [u8; 2]) -> Option<&mut V> {
+        self.table.as_mut()?.get_mut(key)
+    }
+    #[inline(always)]
+    unsafe fn insert_short(&mut self, key: [u8; 2]) -> Result<&mut MaybeUninit<V>, &mut V> {
+        let allocator = &self.allocator;
+        self.table
+            .get_or_insert_with(|| Table1::new_in(allocator.clone()))
+            .insert(key)
+    }
+    /// Moves all entries of `other` into `self`, and calls `f` with keys, insertion results
+    /// and values of `other`.
+    ///
+    /// # Safety
+    ///
+    /// `f` should initialize the resulted `MaybeUninit` immedidately.
+    pub unsafe fn merge<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        if let Some(table) = other.table {
+            let allocator = &self.allocator;
+            self.table
+                .get_or_insert_with(|| Table1::new_in(allocator.clone()))
+                .merge(table, |key, result, value| {
+                    f(K::from_bytes(short_key_bytes(&key)), result, value)
+                });
+        }
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.table.iter().flat_map(|x| {
+            x.iter()
+                .map(|(key, value)| (unsafe { K::from_bytes(short_key_bytes(key)) }, value))
+        })
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.table.iter_mut().flat_map(|x| {
+            x.iter_mut()
+                .map(|(key, value)| (unsafe { K::from_bytes(short_key_bytes(key)) }, value))
+        })
+    }
+}
+
+pub struct ShortBucketIntoIter<K, V, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    table: Option<Table1IntoIter<V, A>>,
+    _phantom: PhantomData<K>,
+}
+
+impl<K, V, A> Iterator for ShortBucketIntoIter<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Item = (Box<K>, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.table.as_mut()?.next()?;
+        Some((unsafe { boxed(short_key_bytes(&key)) }, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.table.as_ref().map_or((0, Some(0)), |x| x.size_hint())
+    }
+}
+
+impl<K, V, A> IntoIterator for UnsizedShortBucket<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Item = (Box<K>, V);
+
+    type IntoIter = ShortBucketIntoIter<K, V, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ShortBucketIntoIter {
+            table: self.table.map(Table1IntoIter::new),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Buckets are dropped as soon as all their entries are emitted, and short keys come last.
+pub struct IntoIter<K, V, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    current: Option<BucketIntoIter<K, V, A>>,
+    tables: std::array::IntoIter<UnsizedBucket<K, V, A>, BUCKETS>,
+    short: ShortBucketIntoIter<K, V, A>,
+}
+
+impl<K, V, A> Iterator for IntoIter<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Item = (Box<K>, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(current) = self.current.as_mut() {
+            if let Some(item) = current.next() {
+                return Some(item);
+            }
+            self.current = self.tables.next().map(IntoIterator::into_iter);
+        }
+        self.short.next()
+    }
+}
+
+impl<K, V, A> IntoIterator for TwolevelUnsizedHashtable<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Item = (Box<K>, V);
+
+    type IntoIter = IntoIter<K, V, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut tables = self.tables.into_iter();
+        IntoIter {
+            current: tables.next().map(IntoIterator::into_iter),
+            tables,
+            short: self.short.into_iter(),
+        }
+    }
+}
@@ -1,9 +1,12 @@
+use crate::arena::Arena;
+use crate::bloom_filter::BloomFilter;
 use crate::container::HeapContainer;
-use crate::table0::{Slot, Table0};
-use crate::table1::Table1;
-use crate::traits::{FastHash, Key, UnsizedKey};
+use crate::frozen_table::FrozenTable;
+use crate::string_column::{Offset, StringColumn};
+use crate::table0::{Slot, Table0, Table0IntoIter};
+use crate::table1::{Table1, Table1IntoIter};
+use crate::traits::{FastHash, HashtableLike, Key, UnsizedKey};
 use crate::utils::read_le;
-use bumpalo::Bump;
 use std::alloc::Allocator;
 use std::intrinsics::unlikely;
 use std::marker::PhantomData;
@@ -16,13 +19,8 @@ where
     K: UnsizedKey + ?Sized,
     A: Allocator + Clone,
 {
-    pub(crate) arena: Bump,
     pub(crate) table0: Table1<V, A>,
-    pub(crate) table1: Table0<InlineKey<0>, V, HeapContainer<Slot<InlineKey<0>, V>, A>, A>,
-    pub(crate) table2: Table0<InlineKey<1>, V, HeapContainer<Slot<InlineKey<1>, V>, A>, A>,
-    pub(crate) table3: Table0<InlineKey<2>, V, HeapContainer<Slot<InlineKey<2>, V>, A>, A>,
-    pub(crate) table4: Table0<FallbackKey, V, HeapContainer<Slot<FallbackKey, V>, A>, A>,
-    pub(crate) _phantom: PhantomData<K>,
+    pub(crate) tiers: Tiers<K, V, A>,
 }
 
 impl<K, V, A> UnsizedHashtable<K, V, A>
@@ -38,13 +36,400 @@ where
 impl<K, V, A> UnsizedHashtable<K, V, A>
 where
     K: UnsizedKey + ?Sized,
-    A: Allocator + Clone + Default,
+    A: Allocator + Clone,
 {
-    /// The bump for strings doesn't allocate memory by `A`.
     pub fn new_in(allocator: A) -> Self {
         Self {
-            arena: Bump::new(),
             table0: Table1::new_in(allocator.clone()),
+            tiers: Tiers::new_in(allocator),
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table0.len() + self.tiers.len()
+    }
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.table0.capacity() + self.tiers.capacity()
+    }
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        unsafe { self.get_prepared(Prepared::new(key.as_bytes())) }
+    }
+    #[inline(always)]
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        unsafe { self.get_prepared_mut(Prepared::new(key.as_bytes())) }
+    }
+    #[inline(always)]
+    pub unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        self.insert_prepared::<true>(Prepared::new(key.as_bytes()))
+    }
+    /// Looks up all values of `column`, and calls `f` with indexes of rows and the values.
+    ///
+    /// Keys are hashed and dispatched to their tiers in chunks on the stack before probing.
+    /// Null rows are missed.
+    pub fn get_column<O, F>(&self, column: &StringColumn<'_, O>, mut f: F)
+    where
+        O: Offset,
+        F: FnMut(usize, Option<&V>),
+    {
+        prepare_column(column, |i, key| {
+            f(i, key.and_then(|key| unsafe { self.get_prepared(key) }))
+        });
+    }
+    /// Inserts all non-null values of `column`, and calls `f` with indexes of rows and
+    /// insertion results.
+    ///
+    /// Keys are hashed and dispatched to their tiers in chunks on the stack before insertion.
+    /// Long keys are copied into the arena only if they're new.
+    ///
+    /// # Safety
+    ///
+    /// `f` should initialize the resulted `MaybeUninit` immedidately. Values of `column`
+    /// should be valid keys of type `K`.
+    pub unsafe fn insert_column<O, F>(&mut self, column: &StringColumn<'_, O>, mut f: F)
+    where
+        O: Offset,
+        F: FnMut(usize, Result<&mut MaybeUninit<V>, &mut V>),
+    {
+        prepare_column(column, |i, key| {
+            if let Some(key) = key {
+                f(i, self.insert_prepared::<true>(key));
+            }
+        });
+    }
+    #[inline(always)]
+    unsafe fn get_prepared(&self, key: Prepared<'_>) -> Option<&V> {
+        match key {
+            Prepared::Short(key) => self.table0.get(key),
+            key => self.tiers.get(key),
+        }
+    }
+    #[inline(always)]
+    unsafe fn get_prepared_mut(&mut self, key: Prepared<'_>) -> Option<&mut V> {
+        match key {
+            Prepared::Short(key) => self.table0.get_mut(key),
+            key => self.tiers.get_mut(key),
+        }
+    }
+    /// Long keys are copied into the arena if `COPY` is set, or borrowed otherwise.
+    #[inline(always)]
+    unsafe fn insert_prepared<const COPY: bool>(
+        &mut self,
+        key: Prepared<'_>,
+    ) -> Result<&mut MaybeUninit<V>, &mut V> {
+        match key {
+            Prepared::Short(key) => self.table0.insert(key),
+            key => self.tiers.insert::<COPY>(key),
+        }
+    }
+    /// Inserts `key` like `insert`, and copies it into the arena if it's new. Long keys are
+    /// stored as the copy, so the table never holds another one.
+    #[inline(always)]
+    pub(crate) unsafe fn insert_copied(
+        &mut self,
+        key: &K,
+    ) -> Result<(NonNull<[u8]>, &mut MaybeUninit<V>), &mut V> {
+        let bytes = key.as_bytes();
+        match Prepared::new(bytes) {
+            Prepared::Short(key) => match self.table0.insert(key) {
+                Ok(x) => Ok((NonNull::from(self.tiers.arena.alloc_slice_copy(bytes)), x)),
+                Err(x) => Err(x),
+            },
+            key => self.tiers.insert_copied(key, bytes),
+        }
+    }
+    /// Builds a Bloom filter of hashes of all keys, which should be probed by `key_hash`.
+    pub fn build_bloom_filter(&self, bits_per_key: usize) -> BloomFilter<A> {
+        let mut filter =
+            BloomFilter::with_capacity_in(self.len(), bits_per_key, self.tiers.allocator().clone());
+        for (key, _) in self.table0.iter() {
+            filter.insert(u16::from_le_bytes(*key).fast_hash());
+        }
+        for hash in self.tiers.hashes() {
+            filter.insert(hash);
+        }
+        filter
+    }
+    /// Shrinks the table and makes it read-only.
+    pub fn freeze(mut self) -> FrozenTable<Self> {
+        self.tiers.shrink_to_fit();
+        FrozenTable::new(self)
+    }
+    /// Moves all entries of `other` into `self`, and calls `f` with keys, insertion results
+    /// and values of `other`.
+    ///
+    /// Long keys of `other` are not copied, since `self` takes over its arena.
+    ///
+    /// # Safety
+    ///
+    /// `f` should initialize the resulted `MaybeUninit` immedidately.
+    pub unsafe fn merge<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        let Self { table0, tiers } = other;
+        self.table0.merge(table0, |key, result, value| {
+            f(K::from_bytes(short_key_bytes(&key)), result, value)
+        });
+        self.tiers.merge(tiers, f);
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.tiers.iter().chain(
+            self.table0
+                .iter()
+                .map(|(key, value)| (unsafe { K::from_bytes(short_key_bytes(key)) }, value)),
+        )
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.tiers.iter_mut().chain(
+            self.table0
+                .iter_mut()
+                .map(|(key, value)| (unsafe { K::from_bytes(short_key_bytes(key)) }, value)),
+        )
+    }
+    /// Removes `key` and returns its value. The memory of long keys is given back to the arena.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        unsafe {
+            match Prepared::new(key.as_bytes()) {
+                Prepared::Short(key) => self.table0.remove(key),
+                key => self.tiers.remove(key),
+            }
+        }
+    }
+    /// Removes all entries that `f` returns `false` for, tier by tier.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.tiers.retain(&mut f);
+        self.table0
+            .retain(|key, value| f(unsafe { K::from_bytes(short_key_bytes(key)) }, value));
+    }
+    /// Returns the entry of `key`, which is only inserted if the vacant entry is filled.
+    pub fn entry<'b>(&mut self, key: &'b K) -> Entry<'_, 'b, K, V, A> {
+        let key = Prepared::new(key.as_bytes());
+        let this = self as *mut Self;
+        // The borrow of `self` ends if the key is vacant.
+        unsafe {
+            match (*this).get_prepared_mut(key) {
+                Some(value) => Entry::Occupied(value),
+                None => Entry::Vacant(VacantEntry {
+                    table: &mut *this,
+                    key,
+                }),
+            }
+        }
+    }
+    /// Appends all keys to `offsets` and `data` tier by tier, in the order of `iter`.
+    ///
+    /// `offsets` should be empty or end with `data.len()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if offsets overflow.
+    pub fn export_keys<O: Offset>(&self, offsets: &mut Vec<O>, data: &mut Vec<u8>) {
+        if offsets.is_empty() {
+            offsets.push(O::from_usize(data.len()));
+        }
+        offsets.reserve(self.len());
+        self.tiers.export_keys(offsets, data);
+        data.reserve(self.table0.len() * 2);
+        for (key, _) in self.table0.iter() {
+            data.extend_from_slice(short_key_bytes(key));
+            offsets.push(O::from_usize(data.len()));
+        }
+    }
+    /// Appends all values to `values` tier by tier, in the order of `iter`.
+    pub fn export_values(&self, values: &mut Vec<V>)
+    where
+        V: Clone,
+    {
+        values.reserve(self.len());
+        self.tiers.export_values(values);
+        values.extend(self.table0.iter().map(|(_, value)| value.clone()));
+    }
+    /// Returns a cursor exporting keys and values in chunks, in the order of `iter`.
+    pub fn export(&self) -> UnsizedExport<impl Iterator<Item = (&[u8], &V)>> {
+        UnsizedExport {
+            iter: self.iter().map(|(key, value)| (key.as_bytes(), value)),
+        }
+    }
+}
+
+/// A cursor exporting keys and values of an `UnsizedHashtable` in chunks.
+pub struct UnsizedExport<I> {
+    iter: I,
+}
+
+impl<'a, V, I> UnsizedExport<I>
+where
+    V: Clone + 'a,
+    I: Iterator<Item = (&'a [u8], &'a V)>,
+{
+    /// Appends at most `rows` keys and values like `export_keys` and `export_values`, and
+    /// returns the number of appended rows, which is zero only if the export is finished.
+    ///
+    /// # Panics
+    ///
+    /// Panics if offsets overflow.
+    pub fn next_chunk<O: Offset>(
+        &mut self,
+        rows: usize,
+        offsets: &mut Vec<O>,
+        data: &mut Vec<u8>,
+        values: &mut Vec<V>,
+    ) -> usize {
+        if offsets.is_empty() {
+            offsets.push(O::from_usize(data.len()));
+        }
+        let mut n = 0;
+        for (key, value) in self.iter.by_ref().take(rows) {
+            data.extend_from_slice(key);
+            offsets.push(O::from_usize(data.len()));
+            values.push(value.clone());
+            n += 1;
+        }
+        n
+    }
+}
+
+pub enum Entry<'a, 'b, K, V, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    Occupied(&'a mut V),
+    Vacant(VacantEntry<'a, 'b, K, V, A>),
+}
+
+impl<'a, 'b, K, V, A> Entry<'a, 'b, K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    pub fn or_insert(self, value: V) -> &'a mut V {
+        self.or_insert_with(|| value)
+    }
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(x) => x,
+            Entry::Vacant(x) => x.insert(f()),
+        }
+    }
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(x) => {
+                f(x);
+                Entry::Occupied(x)
+            }
+            Entry::Vacant(x) => Entry::Vacant(x),
+        }
+    }
+}
+
+pub struct VacantEntry<'a, 'b, K, V, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    table: &'a mut UnsizedHashtable<K, V, A>,
+    key: Prepared<'b>,
+}
+
+impl<'a, 'b, K, V, A> VacantEntry<'a, 'b, K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    pub fn insert(self, value: V) -> &'a mut V {
+        match unsafe { self.table.insert_prepared::<true>(self.key) } {
+            Ok(x) => x.write(value),
+            Err(_) => unreachable!(),
+        }
+    }
+}
+
+pub struct IntoIter<K, V, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    table0: Table1IntoIter<V, A>,
+    tiers: TiersIntoIter<K, V, A>,
+}
+
+impl<K, V, A> Iterator for IntoIter<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Item = (Box<K>, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.tiers.next() {
+            return Some(item);
+        }
+        let (key, value) = self.table0.next()?;
+        Some((unsafe { boxed(short_key_bytes(&key)) }, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.table0.size_hint().0 + self.tiers.size_hint().0;
+        (len, Some(len))
+    }
+}
+
+impl<K, V, A> IntoIterator for UnsizedHashtable<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Item = (Box<K>, V);
+
+    type IntoIter = IntoIter<K, V, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            table0: Table1IntoIter::new(self.table0),
+            tiers: self.tiers.into_iter(),
+        }
+    }
+}
+
+/// The length tiers of keys longer than two bytes, and the arena of long keys, which are
+/// shared by `UnsizedHashtable` and buckets of `TwolevelUnsizedHashtable`.
+pub(crate) struct Tiers<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    pub(crate) arena: Arena<A>,
+    pub(crate) table1: Table0<InlineKey<0>, V, HeapContainer<Slot<InlineKey<0>, V>, A>, A>,
+    pub(crate) table2: Table0<InlineKey<1>, V, HeapContainer<Slot<InlineKey<1>, V>, A>, A>,
+    pub(crate) table3: Table0<InlineKey<2>, V, HeapContainer<Slot<InlineKey<2>, V>, A>, A>,
+    pub(crate) table4: Table0<FallbackKey, V, HeapContainer<Slot<FallbackKey, V>, A>, A>,
+    pub(crate) _phantom: PhantomData<K>,
+}
+
+impl<K, V, A> Tiers<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    pub(crate) fn new_in(allocator: A) -> Self {
+        Self {
+            arena: Arena::new_in(allocator.clone()),
             table1: Table0::with_capacity_in(128, allocator.clone()),
             table2: Table0::with_capacity_in(128, allocator.clone()),
             table3: Table0::with_capacity_in(128, allocator.clone()),
@@ -52,82 +437,573 @@ where
             _phantom: PhantomData,
         }
     }
+    /// Tiers are allocated on their first insertions.
+    pub(crate) fn empty_in(allocator: A) -> Self {
+        Self {
+            arena: Arena::new_in(allocator.clone()),
+            table1: Table0::empty_in(allocator.clone()),
+            table2: Table0::empty_in(allocator.clone()),
+            table3: Table0::empty_in(allocator.clone()),
+            table4: Table0::empty_in(allocator),
+            _phantom: PhantomData,
+        }
+    }
     #[inline(always)]
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
+    pub(crate) fn allocator(&self) -> &A {
+        &self.table1.allocator
     }
     #[inline(always)]
-    pub fn len(&self) -> usize {
-        self.table0.len()
-            + self.table1.len()
-            + self.table2.len()
-            + self.table3.len()
-            + self.table4.len()
+    pub(crate) fn len(&self) -> usize {
+        self.table1.len() + self.table2.len() + self.table3.len() + self.table4.len()
     }
     #[inline(always)]
-    pub fn capacity(&self) -> usize {
-        self.table0.capacity()
-            + self.table1.capacity()
+    pub(crate) fn capacity(&self) -> usize {
+        self.table1.capacity()
             + self.table2.capacity()
             + self.table3.capacity()
             + self.table4.capacity()
     }
+    /// Keys of at most two bytes are never found in tiers.
     #[inline(always)]
-    pub fn get(&self, key: &K) -> Option<&V> {
-        let key = key.as_bytes();
-        match key.len() {
-            _ if key.last().copied() == Some(0) => unsafe {
-                self.table4.get(&FallbackKey::new(key))
-            },
-            0 => self.table0.get([0, 0]),
-            1 => self.table0.get([key[0], 0]),
-            2 => self.table0.get([key[0], key[1]]),
-            3..=8 => unsafe {
-                let mut t = [0u64; 1];
-                t[0] = read_le(key.as_ptr(), key.len());
-                let t = std::mem::transmute::<_, InlineKey<0>>(t);
-                self.table1.get(&t)
-            },
-            9..=16 => unsafe {
-                let mut t = [0u64; 2];
-                t[0] = (key.as_ptr() as *const u64).read_unaligned();
-                t[1] = read_le(key.as_ptr().offset(8), key.len() - 8);
-                let t = std::mem::transmute::<_, InlineKey<1>>(t);
-                self.table2.get(&t)
-            },
-            17..=24 => unsafe {
-                let mut t = [0u64; 3];
-                t[0] = (key.as_ptr() as *const u64).read_unaligned();
-                t[1] = (key.as_ptr() as *const u64).offset(1).read_unaligned();
-                t[2] = read_le(key.as_ptr().offset(16), key.len() - 16);
-                let t = std::mem::transmute::<_, InlineKey<2>>(t);
-                self.table3.get(&t)
-            },
-            _ => unsafe { self.table4.get(&FallbackKey::new(key)) },
+    pub(crate) unsafe fn get(&self, key: Prepared<'_>) -> Option<&V> {
+        match key {
+            Prepared::Short(_) => None,
+            Prepared::Inline0(key, hash) => self.table1.get_with_hash(&key, hash),
+            Prepared::Inline1(key, hash) => self.table2.get_with_hash(&key, hash),
+            Prepared::Inline2(key, hash) => self.table3.get_with_hash(&key, hash),
+            Prepared::Fallback(key, hash) => self
+                .table4
+                .get_with_hash(&FallbackKey::with_hash(key, hash), hash),
+        }
+    }
+    #[inline(always)]
+    pub(crate) unsafe fn get_mut(&mut self, key: Prepared<'_>) -> Option<&mut V> {
+        match key {
+            Prepared::Short(_) => None,
+            Prepared::Inline0(key, hash) => self.table1.get_with_hash_mut(&key, hash),
+            Prepared::Inline1(key, hash) => self.table2.get_with_hash_mut(&key, hash),
+            Prepared::Inline2(key, hash) => self.table3.get_with_hash_mut(&key, hash),
+            Prepared::Fallback(key, hash) => self
+                .table4
+                .get_with_hash_mut(&FallbackKey::with_hash(key, hash), hash),
+        }
+    }
+    /// Long keys are copied into the arena if `COPY` is set, or borrowed otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` has at most two bytes.
+    #[inline(always)]
+    pub(crate) unsafe fn insert<const COPY: bool>(
+        &mut self,
+        key: Prepared<'_>,
+    ) -> Result<&mut MaybeUninit<V>, &mut V> {
+        match key {
+            Prepared::Short(_) => panic!("keys of at most two bytes are not stored in tiers"),
+            Prepared::Inline0(key, hash) => {
+                reserve(&mut self.table1);
+                self.table1.insert_with_hash(key, hash)
+            }
+            Prepared::Inline1(key, hash) => {
+                reserve(&mut self.table2);
+                self.table2.insert_with_hash(key, hash)
+            }
+            Prepared::Inline2(key, hash) => {
+                reserve(&mut self.table3);
+                self.table3.insert_with_hash(key, hash)
+            }
+            Prepared::Fallback(key, hash) => {
+                reserve(&mut self.table4);
+                match self
+                    .table4
+                    .insert_slot_with_hash(FallbackKey::with_hash(key, hash), hash)
+                {
+                    Ok(slot) => {
+                        if COPY {
+                            let key = self.arena.alloc_slice_copy(key);
+                            slot.key.write(FallbackKey::with_hash(key, hash));
+                        }
+                        Ok(&mut slot.val)
+                    }
+                    Err(x) => Err(x),
+                }
+            }
         }
     }
+    /// Inserts `key`, whose bytes are `bytes`, and copies it into the arena if it's new.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` has at most two bytes.
+    #[inline(always)]
+    pub(crate) unsafe fn insert_copied(
+        &mut self,
+        key: Prepared<'_>,
+        bytes: &[u8],
+    ) -> Result<(NonNull<[u8]>, &mut MaybeUninit<V>), &mut V> {
+        let result = match key {
+            Prepared::Short(_) => panic!("keys of at most two bytes are not stored in tiers"),
+            Prepared::Inline0(key, hash) => {
+                reserve(&mut self.table1);
+                self.table1.insert_with_hash(key, hash)
+            }
+            Prepared::Inline1(key, hash) => {
+                reserve(&mut self.table2);
+                self.table2.insert_with_hash(key, hash)
+            }
+            Prepared::Inline2(key, hash) => {
+                reserve(&mut self.table3);
+                self.table3.insert_with_hash(key, hash)
+            }
+            Prepared::Fallback(key, hash) => {
+                reserve(&mut self.table4);
+                return match self
+                    .table4
+                    .insert_slot_with_hash(FallbackKey::with_hash(key, hash), hash)
+                {
+                    Ok(slot) => {
+                        let key = self.arena.alloc_slice_copy(key);
+                        slot.key.write(FallbackKey::with_hash(key, hash));
+                        Ok((NonNull::from(key), &mut slot.val))
+                    }
+                    Err(x) => Err(x),
+                };
+            }
+        };
+        result.map(|x| (NonNull::from(self.arena.alloc_slice_copy(bytes)), x))
+    }
+    /// The memory of long keys is given back to the arena.
+    pub(crate) unsafe fn remove(&mut self, key: Prepared<'_>) -> Option<V> {
+        match key {
+            Prepared::Short(_) => None,
+            Prepared::Inline0(key, hash) => self.table1.remove_with_hash(&key, hash).map(|x| x.1),
+            Prepared::Inline1(key, hash) => self.table2.remove_with_hash(&key, hash).map(|x| x.1),
+            Prepared::Inline2(key, hash) => self.table3.remove_with_hash(&key, hash).map(|x| x.1),
+            Prepared::Fallback(key, hash) => {
+                let key = FallbackKey::with_hash(key, hash);
+                let (key, value) = self.table4.remove_with_hash(&key, hash)?;
+                self.arena.dealloc_slice(key.key.unwrap().as_ref());
+                Some(value)
+            }
+        }
+    }
+    pub(crate) fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        unsafe {
+            let arena = &mut self.arena;
+            self.table4.retain(
+                |key, value| f(K::from_bytes(key.key.unwrap().as_ref()), value),
+                |key| arena.dealloc_slice(key.key.unwrap().as_ref()),
+            );
+            self.table1.retain(
+                |key, value| f(K::from_bytes(inline_key_bytes(key)), value),
+                |_| (),
+            );
+            self.table2.retain(
+                |key, value| f(K::from_bytes(inline_key_bytes(key)), value),
+                |_| (),
+            );
+            self.table3.retain(
+                |key, value| f(K::from_bytes(inline_key_bytes(key)), value),
+                |_| (),
+            );
+        }
+    }
+    /// Long keys of `other` are not copied, since `self` takes over its arena.
+    pub(crate) unsafe fn merge<F>(&mut self, other: Self, mut f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        let Self {
+            arena,
+            table1,
+            table2,
+            table3,
+            table4,
+            ..
+        } = other;
+        self.arena.adopt(arena);
+        reserve_additional(&mut self.table1, table1.len());
+        self.table1.merge(table1, |key, result, value| {
+            f(K::from_bytes(inline_key_bytes(&key)), result, value)
+        });
+        reserve_additional(&mut self.table2, table2.len());
+        self.table2.merge(table2, |key, result, value| {
+            f(K::from_bytes(inline_key_bytes(&key)), result, value)
+        });
+        reserve_additional(&mut self.table3, table3.len());
+        self.table3.merge(table3, |key, result, value| {
+            f(K::from_bytes(inline_key_bytes(&key)), result, value)
+        });
+        reserve_additional(&mut self.table4, table4.len());
+        self.table4.merge(table4, |key, result, value| {
+            f(K::from_bytes(key.key.unwrap().as_ref()), result, value)
+        });
+    }
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        unsafe {
+            self.table4
+                .iter()
+                .map(|(key, value)| (K::from_bytes(key.key.unwrap().as_ref()), value))
+                .chain(
+                    self.table1
+                        .iter()
+                        .map(|(key, value)| (K::from_bytes(inline_key_bytes(key)), value)),
+                )
+                .chain(
+                    self.table2
+                        .iter()
+                        .map(|(key, value)| (K::from_bytes(inline_key_bytes(key)), value)),
+                )
+                .chain(
+                    self.table3
+                        .iter()
+                        .map(|(key, value)| (K::from_bytes(inline_key_bytes(key)), value)),
+                )
+        }
+    }
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        unsafe {
+            self.table4
+                .iter_mut()
+                .map(|(key, value)| (K::from_bytes(key.key.unwrap().as_ref()), value))
+                .chain(
+                    self.table1
+                        .iter_mut()
+                        .map(|(key, value)| (K::from_bytes(inline_key_bytes(key)), value)),
+                )
+                .chain(
+                    self.table2
+                        .iter_mut()
+                        .map(|(key, value)| (K::from_bytes(inline_key_bytes(key)), value)),
+                )
+                .chain(
+                    self.table3
+                        .iter_mut()
+                        .map(|(key, value)| (K::from_bytes(inline_key_bytes(key)), value)),
+                )
+        }
+    }
+    /// Hashes of all keys, which are probed by `key_hash`.
+    pub(crate) fn hashes(&self) -> impl Iterator<Item = u64> + '_ {
+        (self.table4.iter().map(|(key, _)| key.hash))
+            .chain(self.table1.iter().map(|(key, _)| key.hash()))
+            .chain(self.table2.iter().map(|(key, _)| key.hash()))
+            .chain(self.table3.iter().map(|(key, _)| key.hash()))
+    }
+    /// Appends all keys to `offsets` and `data`, in the order of `iter`.
+    pub(crate) fn export_keys<O: Offset>(&self, offsets: &mut Vec<O>, data: &mut Vec<u8>) {
+        for (key, _) in self.table4.iter() {
+            data.extend_from_slice(unsafe { key.key.unwrap().as_ref() });
+            offsets.push(O::from_usize(data.len()));
+        }
+        data.reserve(self.table1.len() * 8);
+        for (key, _) in self.table1.iter() {
+            data.extend_from_slice(inline_key_bytes(key));
+            offsets.push(O::from_usize(data.len()));
+        }
+        data.reserve(self.table2.len() * 16);
+        for (key, _) in self.table2.iter() {
+            data.extend_from_slice(inline_key_bytes(key));
+            offsets.push(O::from_usize(data.len()));
+        }
+        data.reserve(self.table3.len() * 24);
+        for (key, _) in self.table3.iter() {
+            data.extend_from_slice(inline_key_bytes(key));
+            offsets.push(O::from_usize(data.len()));
+        }
+    }
+    /// Appends all values to `values`, in the order of `iter`.
+    pub(crate) fn export_values(&self, values: &mut Vec<V>)
+    where
+        V: Clone,
+    {
+        values.extend(self.table4.iter().map(|(_, value)| value.clone()));
+        values.extend(self.table1.iter().map(|(_, value)| value.clone()));
+        values.extend(self.table2.iter().map(|(_, value)| value.clone()));
+        values.extend(self.table3.iter().map(|(_, value)| value.clone()));
+    }
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.table1.shrink_to_fit();
+        self.table2.shrink_to_fit();
+        self.table3.shrink_to_fit();
+        self.table4.shrink_to_fit();
+    }
+    pub(crate) fn into_iter(self) -> TiersIntoIter<K, V, A> {
+        TiersIntoIter {
+            table1: Table0IntoIter::new(self.table1),
+            table2: Table0IntoIter::new(self.table2),
+            table3: Table0IntoIter::new(self.table3),
+            table4: Table0IntoIter::new(self.table4),
+            _arena: self.arena,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+pub(crate) struct TiersIntoIter<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    table1: Table0IntoIter<InlineKey<0>, V, HeapContainer<Slot<InlineKey<0>, V>, A>, A>,
+    table2: Table0IntoIter<InlineKey<1>, V, HeapContainer<Slot<InlineKey<1>, V>, A>, A>,
+    table3: Table0IntoIter<InlineKey<2>, V, HeapContainer<Slot<InlineKey<2>, V>, A>, A>,
+    table4: Table0IntoIter<FallbackKey, V, HeapContainer<Slot<FallbackKey, V>, A>, A>,
+    _arena: Arena<A>,
+    _phantom: PhantomData<K>,
+}
+
+impl<K, V, A> Iterator for TiersIntoIter<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Item = (Box<K>, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            if let Some((key, value)) = self.table4.next() {
+                return Some((boxed(key.key.unwrap().as_ref()), value));
+            }
+            if let Some((key, value)) = self.table1.next() {
+                return Some((boxed(inline_key_bytes(&key)), value));
+            }
+            if let Some((key, value)) = self.table2.next() {
+                return Some((boxed(inline_key_bytes(&key)), value));
+            }
+            let (key, value) = self.table3.next()?;
+            Some((boxed(inline_key_bytes(&key)), value))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.table1.size_hint().0
+            + self.table2.size_hint().0
+            + self.table3.size_hint().0
+            + self.table4.size_hint().0;
+        (len, Some(len))
+    }
+}
+
+/// Copies `bytes` into a boxed key.
+///
+/// # Safety
+///
+/// `bytes` is a valid key, and `K::from_bytes` returns a reference to the same memory.
+pub(crate) unsafe fn boxed<K: UnsizedKey + ?Sized>(bytes: &[u8]) -> Box<K> {
+    let raw = Box::into_raw(Box::<[u8]>::from(bytes));
+    Box::from_raw(K::from_bytes(&*raw) as *const K as *mut K)
+}
+
+/// An `UnsizedHashtable` borrowing keys from buffers outliving it, so that long keys are
+/// never copied.
+pub struct BorrowedUnsizedHashtable<'a, K, V, A = crate::allocator::Default>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    table: UnsizedHashtable<K, V, A>,
+    _phantom: PhantomData<&'a K>,
+}
+
+impl<'a, K, V, A> BorrowedUnsizedHashtable<'a, K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone + Default,
+{
+    pub fn new() -> Self {
+        Self::new_in(Default::default())
+    }
+}
+
+impl<'a, K, V, A> BorrowedUnsizedHashtable<'a, K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    pub fn new_in(allocator: A) -> Self {
+        Self {
+            table: UnsizedHashtable::new_in(allocator),
+            _phantom: PhantomData,
+        }
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.table.capacity()
+    }
+    #[inline(always)]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.table.get(key)
+    }
     #[inline(always)]
     pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
-        let key = key.as_bytes();
+        self.table.get_mut(key)
+    }
+    /// # Safety
+    ///
+    /// The resulted `MaybeUninit` should be initialized immedidately.
+    #[inline(always)]
+    pub unsafe fn insert(&mut self, key: &'a K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        self.table
+            .insert_prepared::<false>(Prepared::new(key.as_bytes()))
+    }
+    /// Looks up all values of `column` like `UnsizedHashtable::get_column`.
+    pub fn get_column<O, F>(&self, column: &StringColumn<'_, O>, f: F)
+    where
+        O: Offset,
+        F: FnMut(usize, Option<&V>),
+    {
+        self.table.get_column(column, f)
+    }
+    /// Inserts all non-null values of `column` like `UnsizedHashtable::insert_column`.
+    ///
+    /// # Safety
+    ///
+    /// `f` should initialize the resulted `MaybeUninit` immedidately. Values of `column`
+    /// should be valid keys of type `K`.
+    pub unsafe fn insert_column<O, F>(&mut self, column: &StringColumn<'a, O>, mut f: F)
+    where
+        O: Offset,
+        F: FnMut(usize, Result<&mut MaybeUninit<V>, &mut V>),
+    {
+        prepare_column(column, |i, key| {
+            if let Some(key) = key {
+                f(i, self.table.insert_prepared::<false>(key));
+            }
+        });
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.table.iter()
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.table.iter_mut()
+    }
+}
+
+impl<K, V, A> HashtableLike for UnsizedHashtable<K, V, A>
+where
+    K: UnsizedKey + ?Sized,
+    A: Allocator + Clone,
+{
+    type Key = K;
+    type Value = V;
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+
+    unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
+        self.insert(key)
+    }
+
+    unsafe fn merge<F>(&mut self, other: Self, f: F)
+    where
+        F: FnMut(&K, Result<&mut MaybeUninit<V>, &mut V>, V),
+    {
+        self.merge(other, f)
+    }
+
+    type Iter<'a>
+        = impl Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a;
+
+    type IterMut<'a>
+        = impl Iterator<Item = (&'a K, &'a mut V)>
+    where
+        Self: 'a;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.iter_mut()
+    }
+}
+
+/// Returns the hash of `key` that is used by `UnsizedHashtable::build_bloom_filter`.
+#[inline(always)]
+pub fn key_hash<K: UnsizedKey + ?Sized>(key: &K) -> u64 {
+    Prepared::new(key.as_bytes()).hash()
+}
+
+#[inline(always)]
+pub(crate) fn reserve<K: Key, V, A: Allocator + Clone>(
+    table: &mut Table0<K, V, HeapContainer<Slot<K, V>, A>, A>,
+) {
+    if unlikely((table.len() + 1) * 2 > table.capacity()) {
+        if (table.slots.len() >> 22) == 0 {
+            table.grow(2);
+        } else {
+            table.grow(1);
+        }
+    }
+}
+
+#[inline(always)]
+pub(crate) fn reserve_additional<K: Key, V, A: Allocator + Clone>(
+    table: &mut Table0<K, V, HeapContainer<Slot<K, V>, A>, A>,
+    additional: usize,
+) {
+    while (table.len() + additional) * 2 > table.capacity() {
+        if (table.slots.len() >> 22) == 0 {
+            table.grow(2);
+        } else {
+            table.grow(1);
+        }
+    }
+}
+
+/// A key dispatched to its tier with its hash.
+#[derive(Clone, Copy)]
+pub(crate) enum Prepared<'a> {
+    Short([u8; 2]),
+    Inline0(InlineKey<0>, u64),
+    Inline1(InlineKey<1>, u64),
+    Inline2(InlineKey<2>, u64),
+    Fallback(&'a [u8], u64),
+}
+
+impl<'a> Prepared<'a> {
+    #[inline(always)]
+    pub(crate) fn new(key: &'a [u8]) -> Self {
         match key.len() {
-            _ if key.last().copied() == Some(0) => unsafe {
-                self.table4.get_mut(&FallbackKey::new(key))
-            },
-            0 => self.table0.get_mut([0, 0]),
-            1 => self.table0.get_mut([key[0], 0]),
-            2 => self.table0.get_mut([key[0], key[1]]),
+            _ if key.last().copied() == Some(0) => Prepared::Fallback(key, key.fast_hash()),
+            0 => Prepared::Short([0, 0]),
+            1 => Prepared::Short([key[0], 0]),
+            2 => Prepared::Short([key[0], key[1]]),
             3..=8 => unsafe {
                 let mut t = [0u64; 1];
                 t[0] = read_le(key.as_ptr(), key.len());
                 let t = std::mem::transmute::<_, InlineKey<0>>(t);
-                self.table1.get_mut(&t)
+                Prepared::Inline0(t, t.hash())
             },
             9..=16 => unsafe {
                 let mut t = [0u64; 2];
                 t[0] = (key.as_ptr() as *const u64).read_unaligned();
                 t[1] = read_le(key.as_ptr().offset(8), key.len() - 8);
                 let t = std::mem::transmute::<_, InlineKey<1>>(t);
-                self.table2.get_mut(&t)
+                Prepared::Inline1(t, t.hash())
             },
             17..=24 => unsafe {
                 let mut t = [0u64; 3];
@@ -135,153 +1011,66 @@ where
                 t[1] = (key.as_ptr() as *const u64).offset(1).read_unaligned();
                 t[2] = read_le(key.as_ptr().offset(16), key.len() - 16);
                 let t = std::mem::transmute::<_, InlineKey<2>>(t);
-                self.table3.get_mut(&t)
+                Prepared::Inline2(t, t.hash())
             },
-            _ => unsafe { self.table4.get_mut(&FallbackKey::new(key)) },
+            _ => Prepared::Fallback(key, key.fast_hash()),
         }
     }
     #[inline(always)]
-    pub unsafe fn insert(&mut self, key: &K) -> Result<&mut MaybeUninit<V>, &mut V> {
-        let key = key.as_bytes();
-        match key.len() {
-            _ if key.last().copied() == Some(0) => {
-                if unlikely((self.table4.len() + 1) * 2 > self.table4.capacity()) {
-                    if (self.table4.slots.len() >> 22) == 0 {
-                        self.table4.grow(2);
-                    } else {
-                        self.table4.grow(1);
-                    }
-                }
-                let s = self.arena.alloc_slice_copy(key);
-                self.table4.insert(FallbackKey::new(s))
-            }
-            0 => self.table0.insert([0, 0]),
-            1 => self.table0.insert([key[0], 0]),
-            2 => self.table0.insert([key[0], key[1]]),
-            3..=8 => {
-                if unlikely((self.table1.len() + 1) * 2 > self.table1.capacity()) {
-                    if (self.table1.slots.len() >> 22) == 0 {
-                        self.table1.grow(2);
-                    } else {
-                        self.table1.grow(1);
-                    }
-                }
-                let mut t = [0u64; 1];
-                t[0] = read_le(key.as_ptr(), key.len());
-                let t = std::mem::transmute::<_, InlineKey<0>>(t);
-                self.table1.insert(t)
-            }
-            9..=16 => {
-                if unlikely((self.table2.len() + 1) * 2 > self.table2.capacity()) {
-                    if (self.table2.slots.len() >> 22) == 0 {
-                        self.table2.grow(2);
-                    } else {
-                        self.table2.grow(1);
-                    }
-                }
-                let mut t = [0u64; 2];
-                t[0] = (key.as_ptr() as *const u64).read_unaligned();
-                t[1] = read_le(key.as_ptr().offset(8), key.len() - 8);
-                let t = std::mem::transmute::<_, InlineKey<1>>(t);
-                self.table2.insert(t)
-            }
-            17..=24 => {
-                if unlikely((self.table3.len() + 1) * 2 > self.table3.capacity()) {
-                    if (self.table3.slots.len() >> 22) == 0 {
-                        self.table3.grow(2);
-                    } else {
-                        self.table3.grow(1);
-                    }
-                }
-                let mut t = [0u64; 3];
-                t[0] = (key.as_ptr() as *const u64).read_unaligned();
-                t[1] = (key.as_ptr() as *const u64).offset(1).read_unaligned();
-                t[2] = read_le(key.as_ptr().offset(16), key.len() - 16);
-                let t = std::mem::transmute::<_, InlineKey<2>>(t);
-                self.table3.insert(t)
-            }
-            _ => {
-                if unlikely((self.table4.len() + 1) * 2 > self.table4.capacity()) {
-                    if (self.table4.slots.len() >> 22) == 0 {
-                        self.table4.grow(2);
-                    } else {
-                        self.table4.grow(1);
-                    }
-                }
-                let s = self.arena.alloc_slice_copy(key);
-                self.table4.insert(FallbackKey::new(s))
-            }
+    pub(crate) fn hash(&self) -> u64 {
+        match self {
+            Prepared::Short(key) => u16::from_le_bytes(*key).fast_hash(),
+            Prepared::Inline0(_, hash)
+            | Prepared::Inline1(_, hash)
+            | Prepared::Inline2(_, hash)
+            | Prepared::Fallback(_, hash) => *hash,
         }
     }
-    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
-        self.table4
-            .iter()
-            .map(|(key, value)| {
-                (
-                    unsafe { UnsizedKey::from_bytes(key.key.unwrap().as_ref()) },
-                    value,
-                )
-            })
-            .chain(self.table1.iter().map(|(key, value)| {
-                let bytes = key.1.get().to_le_bytes();
-                unsafe {
-                    for i in (0..=7).rev() {
-                        if bytes[i] != 0 {
-                            return (
-                                UnsizedKey::from_bytes(std::slice::from_raw_parts(
-                                    key as *const _ as *const u8,
-                                    i + 1,
-                                )),
-                                value,
-                            );
-                        }
-                    }
-                }
-                unreachable!()
-            }))
-            .chain(self.table2.iter().map(|(key, value)| {
-                let bytes = key.1.get().to_le_bytes();
-                unsafe {
-                    for i in (0..=7).rev() {
-                        if bytes[i] != 0 {
-                            return (
-                                UnsizedKey::from_bytes(std::slice::from_raw_parts(
-                                    key as *const _ as *const u8,
-                                    i + 9,
-                                )),
-                                value,
-                            );
-                        }
-                    }
-                }
-                unreachable!()
-            }))
-            .chain(self.table3.iter().map(|(key, value)| {
-                let bytes = key.1.get().to_le_bytes();
-                unsafe {
-                    for i in (0..=7).rev() {
-                        if bytes[i] != 0 {
-                            return (
-                                UnsizedKey::from_bytes(std::slice::from_raw_parts(
-                                    key as *const _ as *const u8,
-                                    i + 17,
-                                )),
-                                value,
-                            );
-                        }
-                    }
-                }
-                unreachable!()
-            }))
-            .chain(self.table0.iter().map(|(key, value)| unsafe {
-                if key[1] != 0 {
-                    (UnsizedKey::from_bytes(&key[..2]), value)
-                } else if key[0] != 0 {
-                    (UnsizedKey::from_bytes(&key[..1]), value)
-                } else {
-                    (UnsizedKey::from_bytes(&key[..0]), value)
-                }
-            }))
+}
+
+/// Hashes and dispatches rows of `column` chunk by chunk into a buffer on the stack, and calls
+/// `f` with indexes of rows and their prepared keys, so that no batch allocates.
+#[inline(always)]
+fn prepare_column<'a, O, F>(column: &StringColumn<'a, O>, mut f: F)
+where
+    O: Offset,
+    F: FnMut(usize, Option<Prepared<'a>>),
+{
+    const CHUNK: usize = 256;
+    let mut keys = [None; CHUNK];
+    let mut rows = column.iter();
+    let mut offset = 0;
+    loop {
+        let mut n = 0;
+        for (key, row) in keys.iter_mut().zip(rows.by_ref()) {
+            *key = row.map(Prepared::new);
+            n += 1;
+        }
+        for (i, &key) in keys[..n].iter().enumerate() {
+            f(offset + i, key);
+        }
+        if n < CHUNK {
+            return;
+        }
+        offset += CHUNK;
+    }
+}
+
+/// Returns bytes of an inline key, whose length is given by its last non-zero byte.
+#[inline(always)]
+pub(crate) fn inline_key_bytes<const N: usize>(key: &InlineKey<N>) -> &[u8] {
+    let zeros = key.1.get().leading_zeros() as usize / 8;
+    unsafe { std::slice::from_raw_parts(key as *const _ as *const u8, N * 8 + 8 - zeros) }
+}
+
+#[inline(always)]
+pub(crate) fn short_key_bytes(key: &[u8; 2]) -> &[u8] {
+    if key[1] != 0 {
+        &key[..2]
+    } else if key[0] != 0 {
+        &key[..1]
+    } else {
+        &key[..0]
     }
 }
 
@@ -308,15 +1097,22 @@ unsafe impl<const N: usize> Key for InlineKey<N> {
 
 #[derive(Copy, Clone)]
 pub(crate) struct FallbackKey {
-    key: Option<NonNull<[u8]>>,
-    hash: u64,
+    pub(crate) key: Option<NonNull<[u8]>>,
+    pub(crate) hash: u64,
 }
 
+// `key` points to bytes that are never mutated and outlive the table holding it: either
+// in the arena of the table, or borrowed by a `BorrowedUnsizedHashtable` for its lifetime.
+unsafe impl Send for FallbackKey {}
+
+unsafe impl Sync for FallbackKey {}
+
 impl FallbackKey {
-    unsafe fn new(key: &[u8]) -> Self {
+    #[inline(always)]
+    pub(crate) fn with_hash(key: &[u8], hash: u64) -> Self {
         Self {
             key: Some(NonNull::from(key)),
-            hash: key.fast_hash(),
+            hash,
         }
     }
 }
@@ -10,3 +10,12 @@ pub unsafe fn read_le(data: *const u8, len: usize) -> u64 {
         (data.offset(len as isize - 8) as *const u64).read_unaligned() >> s
     }
 }
+
+/// Calls the closure on drop, so it runs if the guarded code panics and is forgotten otherwise.
+pub(crate) struct OnUnwind<F: FnMut()>(pub(crate) F);
+
+impl<F: FnMut()> Drop for OnUnwind<F> {
+    fn drop(&mut self) {
+        (self.0)()
+    }
+}
@@ -0,0 +1,43 @@
+use hashtable::adaptive_hashset::AdaptiveHashset;
+use rand::Rng;
+use std::collections::HashSet;
+
+#[test]
+fn adaptive_hashset_stages() {
+    let mut set = AdaptiveHashset::<u64>::new();
+    for i in 0..8u64 {
+        assert!(set.insert(i));
+        assert!(!set.insert(i));
+    }
+    assert!(set.is_array());
+    assert!(set.insert(8));
+    assert!(!set.is_array());
+    for i in 0..200000u64 {
+        set.insert(i);
+    }
+    assert!(set.is_twolevel());
+    assert_eq!(set.len(), 200000);
+    assert!(set.contains(&0));
+    assert!(!set.contains(&200000));
+}
+
+#[test]
+fn adaptive_hashset_merge() {
+    let mut groups = Vec::new();
+    let mut base = HashSet::new();
+    for size in [0usize, 3, 8, 100, 70000, 5, 200000] {
+        let mut set = AdaptiveHashset::<u64>::new();
+        for _ in 0..size {
+            let key = rand::thread_rng().gen_range(0..1 << 20);
+            set.insert(key);
+            base.insert(key);
+        }
+        groups.push(set);
+    }
+    let mut result = AdaptiveHashset::<u64>::new();
+    for set in groups {
+        result.merge(set);
+    }
+    assert_eq!(result.len(), base.len());
+    assert!(result.iter().all(|x| base.contains(x)));
+}
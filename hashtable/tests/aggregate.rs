@@ -0,0 +1,88 @@
+use hashtable::aggregate::{AggregateFunction, Any, Avg, Count, Max, Min, Sum};
+use hashtable::hashtable::Hashtable;
+use hashtable::traits::HashtableLike;
+use hashtable::twolevel_hashtable::TwolevelHashtable;
+use hashtable::unsized_hashtable::UnsizedHashtable;
+use rand::Rng;
+use std::collections::HashMap;
+
+#[test]
+fn group_by() {
+    let mut keys = vec![0u32; 1 << 16];
+    keys.fill_with(|| rand::thread_rng().gen_range(0..1 << 10));
+    let mut values = vec![0i32; 1 << 16];
+    values.fill_with(|| rand::thread_rng().gen_range(-1000..1000));
+    let function = (
+        Count::<i32>::new(),
+        Sum::<i32>::new(),
+        Min::<i32>::new(),
+        Max::<i32>::new(),
+        Avg::<i32>::new(),
+    );
+    let args = || values.iter().map(|x| (x, x, x, x, x));
+    let mut table = Hashtable::<u32, _>::new();
+    table.aggregate(&function, &keys[..1 << 15], args());
+    let mut other = Hashtable::<u32, _>::new();
+    other.aggregate(&function, &keys[1 << 15..], args().skip(1 << 15));
+    table.merge_aggregate(other, &function);
+    let mut twolevel = TwolevelHashtable::<u32, _>::new();
+    twolevel.aggregate(&function, &keys, args());
+    let mut base = HashMap::<u32, Vec<i32>>::new();
+    for (&key, &value) in keys.iter().zip(values.iter()) {
+        base.entry(key).or_default().push(value);
+    }
+    assert_eq!(table.len(), base.len());
+    assert_eq!(twolevel.len(), base.len());
+    for (key, group) in base.iter() {
+        let count = group.len() as u64;
+        let sum = group.iter().map(|&x| x as i64).sum::<i64>();
+        let expected = (
+            count,
+            sum,
+            group.iter().min().copied(),
+            group.iter().max().copied(),
+            Some(sum as f64 / count as f64),
+        );
+        let state = table.get(key).unwrap();
+        let mut buf = Vec::new();
+        function.serialize(state, &mut buf);
+        let mut slice = &buf[..];
+        let state = function.deserialize(&mut slice);
+        assert!(slice.is_empty());
+        assert_eq!(function.finalize(state), expected);
+        let state = *twolevel.get(key).unwrap();
+        assert_eq!(function.finalize(state), expected);
+    }
+}
+
+#[test]
+fn group_by_strings() {
+    let sequence = (0..10000u64)
+        .map(|i| format!("{:0>width$}", i % 777, width = (i % 37) as usize))
+        .collect::<Vec<_>>();
+    let keys = sequence.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+    let values = (0..10000u64)
+        .map(|i| (i * 7919 % 1000).to_string())
+        .collect::<Vec<_>>();
+    let function = (Min::<str>::new(), Max::<str>::new(), Any::<str>::new());
+    let args = || values.iter().map(|x| (x.as_str(), x.as_str(), x.as_str()));
+    let mut table = UnsizedHashtable::<str, _>::new();
+    table.aggregate(&function, keys[..5000].iter().copied(), args());
+    let mut other = UnsizedHashtable::<str, _>::new();
+    other.aggregate(&function, keys[5000..].iter().copied(), args().skip(5000));
+    table.merge_aggregate(other, &function);
+    let mut base = HashMap::<&str, Vec<&str>>::new();
+    for (&key, value) in keys.iter().zip(values.iter()) {
+        base.entry(key).or_default().push(value);
+    }
+    assert_eq!(table.len(), base.len());
+    for (&key, group) in base.iter() {
+        let (min, max, any) = function.finalize(table.get(key).unwrap().clone());
+        assert_eq!(min.as_deref(), group.iter().min().copied());
+        assert_eq!(max.as_deref(), group.iter().max().copied());
+        assert_eq!(any.as_deref(), Some(group[0]));
+    }
+    let mut state = Sum::<u8>::new().init();
+    Sum::<u8>::new().update_batch(&mut state, [255u8; 4].iter());
+    assert_eq!(state, 1020u64);
+}
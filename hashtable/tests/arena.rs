@@ -0,0 +1,66 @@
+#![feature(allocator_api)]
+
+use hashtable::arena::Arena;
+use hashtable::unsized_hashtable::UnsizedHashtable;
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Clone, Default)]
+struct Tracker(Arc<AtomicUsize>);
+
+unsafe impl Allocator for Tracker {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.fetch_add(layout.size(), Ordering::Relaxed);
+        Global.allocate(layout)
+    }
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.0.fetch_sub(layout.size(), Ordering::Relaxed);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+fn arena() {
+    let tracker = Tracker::default();
+    let mut arena = Arena::new_in(tracker.clone());
+    assert_eq!(arena.allocated_bytes(), 0);
+    let mut places = Vec::new();
+    for i in 1..1000usize {
+        let layout = Layout::from_size_align(i % 37, 1 << (i % 5)).unwrap();
+        let place = arena.alloc_layout(layout);
+        assert_eq!(place.as_ptr() as usize % layout.align(), 0);
+        unsafe { std::ptr::write_bytes(place.as_ptr(), i as u8, layout.size()) };
+        places.push((place, layout, i as u8));
+    }
+    for &(place, layout, x) in places.iter() {
+        let bytes = unsafe { std::slice::from_raw_parts(place.as_ptr(), layout.size()) };
+        assert!(bytes.iter().all(|&y| y == x));
+    }
+    let long = vec![7u8; 100 << 10];
+    assert_eq!(arena.alloc_slice_copy(&long), &long[..]);
+    assert_eq!(arena.alloc_slice_copy(&[]), &[]);
+    assert!(tracker.0.load(Ordering::Relaxed) >= arena.allocated_bytes());
+    drop(arena);
+    assert_eq!(tracker.0.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn arena_unsized_hashtable() {
+    let tracker = Tracker::default();
+    let mut table = UnsizedHashtable::<[u8], u64, _>::new_in(tracker.clone());
+    let baseline = tracker.0.load(Ordering::Relaxed);
+    for i in 0..10000u64 {
+        let key = format!("{:0>100}", i);
+        unsafe {
+            table.insert(key.as_bytes()).ok().unwrap().write(i);
+        }
+    }
+    assert!(tracker.0.load(Ordering::Relaxed) >= baseline + 10000 * 100);
+    for i in 0..10000u64 {
+        assert_eq!(table.get(format!("{:0>100}", i).as_bytes()), Some(&i));
+    }
+    drop(table);
+    assert_eq!(tracker.0.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,50 @@
+use hashtable::hashtable::Hashtable;
+use hashtable::traits::Key;
+use hashtable::unsized_hashtable::{key_hash, UnsizedHashtable};
+
+#[test]
+fn bloom_filter_hashtable() {
+    let mut table = Hashtable::<u64, u64>::new();
+    for i in 0..100000u64 {
+        unsafe {
+            table.insert(i * 7).ok().unwrap().write(i);
+        }
+    }
+    let filter = table.build_bloom_filter(10);
+    for i in 0..100000u64 {
+        assert!(filter.may_contain((i * 7).hash()));
+        assert!(filter.may_contain_simd((i * 7).hash()));
+    }
+    let hashes = (0..100000u64)
+        .map(|i| (i * 7 + 1).hash())
+        .collect::<Vec<_>>();
+    let mut selection = Vec::new();
+    let count = filter.may_contain_batch(&hashes, &mut selection);
+    let scalar = hashes.iter().filter(|&&x| filter.may_contain(x)).count();
+    assert_eq!(count, scalar);
+    assert!(count < hashes.len() / 20, "false positives = {count}");
+}
+
+#[test]
+fn bloom_filter_unsized_hashtable() {
+    let keys = (0..10000u64)
+        .map(|i| format!("{:0>width$}", i, width = (i % 48) as usize))
+        .chain(["".to_string(), "a".to_string(), "ab".to_string()])
+        .chain(["tail\0".to_string()])
+        .collect::<Vec<_>>();
+    let mut table = UnsizedHashtable::<str, u64>::new();
+    for (i, key) in keys.iter().enumerate() {
+        unsafe {
+            let _ = table.insert(key).map(|x| x.write(i as u64));
+        }
+    }
+    let filter = table.build_bloom_filter(10);
+    for key in keys.iter() {
+        assert!(filter.may_contain(key_hash(key.as_str())));
+    }
+    let misses = (0..10000u64)
+        .map(|i| format!("missing{:0>width$}", i, width = (i % 48) as usize))
+        .filter(|key| filter.may_contain(key_hash(key.as_str())))
+        .count();
+    assert!(misses < 500, "false positives = {misses}");
+}
@@ -0,0 +1,57 @@
+use hashtable::string_column::StringColumn;
+use hashtable::unsized_hashtable::BorrowedUnsizedHashtable;
+use rand::Rng;
+use std::collections::HashMap;
+
+#[test]
+fn borrowed_unsized_hashtable() {
+    let mut keys = Vec::new();
+    for _ in 0..10000 {
+        let mut rng = rand::thread_rng();
+        let mut key = vec![b'x'; rng.gen_range(0..60)];
+        if let Some(last) = key.last_mut() {
+            *last = rng.gen_range(0..4);
+        }
+        keys.push(key);
+    }
+    let data = keys.concat();
+    let range = data.as_ptr_range();
+    let mut offsets = vec![0u32];
+    for key in keys.iter() {
+        offsets.push(offsets.last().unwrap() + key.len() as u32);
+    }
+    let column = StringColumn::new(&offsets, &data);
+    let mut table = BorrowedUnsizedHashtable::<[u8], u32>::new();
+    unsafe {
+        table.insert_column(&column, |_, result| match result {
+            Ok(x) => {
+                x.write(1);
+            }
+            Err(x) => {
+                *x += 1;
+            }
+        });
+    }
+    for key in column.iter().flatten() {
+        match unsafe { table.insert(key) } {
+            Ok(_) => unreachable!(),
+            Err(x) => {
+                *x += 1;
+            }
+        }
+    }
+    let mut base = HashMap::<&[u8], u32>::new();
+    for key in keys.iter() {
+        *base.entry(key).or_default() += 2;
+    }
+    assert_eq!(table.len(), base.len());
+    for (key, value) in table.iter() {
+        assert_eq!(base[key], *value);
+        if key.len() > 24 || key.last().copied() == Some(0) {
+            assert!(range.contains(&key.as_ptr()));
+        }
+    }
+    for (key, value) in base.iter() {
+        assert_eq!(table.get(key), Some(value));
+    }
+}
@@ -0,0 +1,64 @@
+#![allow(dead_code)]
+
+use hashtable::traits::HashtableLike;
+use rand::Rng;
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::collections::HashMap;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Counts bytes that are allocated but not deallocated yet.
+#[derive(Clone, Default)]
+pub struct Tracker(pub Arc<AtomicUsize>);
+
+impl Tracker {
+    pub fn allocated(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+unsafe impl Allocator for Tracker {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.0.fetch_add(layout.size(), Ordering::Relaxed);
+        Global.allocate(layout)
+    }
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.0.fetch_sub(layout.size(), Ordering::Relaxed);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+/// Inserts `n` random keys of every length tier into `table`, initializing values by `init`
+/// and updating existing values by `update`, and returns insertion counts of keys.
+pub fn insert_random_keys<T, F, G>(
+    table: &mut T,
+    n: usize,
+    mut init: F,
+    mut update: G,
+) -> HashMap<Vec<u8>, u64>
+where
+    T: HashtableLike<Key = [u8]>,
+    F: FnMut() -> T::Value,
+    G: FnMut(&mut T::Value),
+{
+    let mut base = HashMap::<Vec<u8>, u64>::new();
+    let mut rng = rand::thread_rng();
+    for _ in 0..n {
+        let len = rng.gen_range(0..50);
+        let mut key = (0..len)
+            .map(|_| rng.gen_range(b'a'..b'e'))
+            .collect::<Vec<_>>();
+        if let Some(last) = key.last_mut() {
+            *last = rng.gen_range(0..4);
+        }
+        match unsafe { table.insert(&key[..]) } {
+            Ok(x) => {
+                x.write(init());
+            }
+            Err(x) => update(x),
+        }
+        *base.entry(key).or_default() += 1;
+    }
+    base
+}
@@ -0,0 +1,72 @@
+use hashtable::concurrent_hashtable::ConcurrentHashtable;
+use rand::Rng;
+use std::collections::HashMap;
+
+#[test]
+fn concurrent_count_distinct() {
+    const THREADS: usize = 8;
+    let mut sequence = vec![0u64; 1 << 22];
+    sequence.fill_with(|| rand::thread_rng().gen_range(0..1 << 18));
+    let mut base = HashMap::<u64, u64>::new();
+    for &s in sequence.iter() {
+        *base.entry(s).or_default() += 1;
+    }
+    let table = ConcurrentHashtable::<u64, u64>::new();
+    std::thread::scope(|scope| {
+        for chunk in sequence.chunks(sequence.len() / THREADS) {
+            let table = &table;
+            scope.spawn(move || {
+                for &s in chunk.iter() {
+                    unsafe {
+                        table.insert(s, |result| match result {
+                            Ok(x) => {
+                                x.write(1);
+                            }
+                            Err(x) => {
+                                *x += 1;
+                            }
+                        });
+                    }
+                    assert!(table.get(&s, |x| x.is_some()));
+                }
+            });
+        }
+    });
+    assert_eq!(base.len(), table.len());
+    for (key, value) in base.iter() {
+        assert_eq!(table.get(key, |x| x.copied()), Some(*value));
+    }
+    let table = table.into_twolevel();
+    assert_eq!(base.len(), table.len());
+    for (key, value) in table.iter() {
+        assert_eq!(base.get(key), Some(value));
+    }
+}
+
+#[test]
+fn concurrent_insert_panic() {
+    let table = ConcurrentHashtable::<u64, String>::new();
+    for s in 1..100u64 {
+        unsafe {
+            table.insert(s, |result| {
+                result.ok().unwrap().write(s.to_string());
+            });
+        }
+    }
+    for s in [0u64, 1000] {
+        let result = std::panic::catch_unwind(|| unsafe {
+            table.insert(s, |_| panic!());
+        });
+        assert!(result.is_err());
+        assert!(table.get(&s, |x| x.is_none()));
+    }
+    assert_eq!(table.len(), 99);
+    unsafe {
+        table.insert(1000, |result| {
+            result.ok().unwrap().write(1000.to_string());
+        });
+    }
+    let table = table.into_twolevel();
+    assert_eq!(table.len(), 100);
+    assert_eq!(table.get(&1000).map(|x| x.as_str()), Some("1000"));
+}
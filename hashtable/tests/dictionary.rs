@@ -0,0 +1,40 @@
+use hashtable::dictionary::StringDictionary;
+use hashtable::string_column::StringColumn;
+
+#[test]
+fn dictionary_encode_decode() {
+    let column = (0..10000u64)
+        .map(|i| format!("{:0>width$}", i % 321, width = (i % 50) as usize))
+        .collect::<Vec<_>>();
+    let mut dictionary = StringDictionary::<str>::new();
+    let mut codes = Vec::new();
+    dictionary.encode_column(column.iter().map(|x| x.as_str()), &mut codes);
+    assert_eq!(codes.len(), column.len());
+    let mut offsets = Vec::<u32>::new();
+    let mut data = Vec::new();
+    dictionary.decode_column(&codes, &mut offsets, &mut data);
+    let decoded = StringColumn::new(&offsets, &data);
+    assert_eq!(decoded.len(), column.len());
+    for (i, key) in column.iter().enumerate() {
+        assert_eq!(decoded.get(i), Some(key.as_bytes()));
+        assert_eq!(dictionary.decode(codes[i]), key);
+        assert_eq!(dictionary.lookup(key), Some(codes[i]));
+    }
+    assert_eq!(dictionary.lookup("missing"), None);
+}
+
+#[test]
+fn dictionary_merge() {
+    let mut a = StringDictionary::<[u8]>::new();
+    let mut b = StringDictionary::<[u8]>::new();
+    for i in 0..1000u32 {
+        a.encode(format!("key{}", i).as_bytes());
+        b.encode(format!("key{}", i * 2).as_bytes());
+    }
+    let remap = a.merge(&b);
+    assert_eq!(remap.len(), b.len());
+    assert_eq!(a.len(), 1500);
+    for (code, key) in b.keys().enumerate() {
+        assert_eq!(a.decode(remap[code]), key);
+    }
+}
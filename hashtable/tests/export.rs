@@ -0,0 +1,84 @@
+use hashtable::hashtable::Hashtable;
+use hashtable::unsized_hashtable::UnsizedHashtable;
+use rand::Rng;
+use std::collections::HashMap;
+
+#[test]
+fn export() {
+    let mut keys = vec![0u64; 1 << 14];
+    keys.fill_with(|| rand::thread_rng().gen_range(0..1 << 10));
+    let mut table = Hashtable::<u64, u64>::new();
+    let mut base = HashMap::<u64, u64>::new();
+    for &key in keys.iter() {
+        match unsafe { table.insert(key) } {
+            Ok(x) => {
+                x.write(1);
+            }
+            Err(x) => {
+                *x += 1;
+            }
+        }
+        *base.entry(key).or_default() += 1;
+    }
+    let (mut keys, mut values) = (Vec::new(), Vec::new());
+    table.export_keys(&mut keys);
+    table.export_values(&mut values);
+    assert_eq!(keys.len(), base.len());
+    assert_eq!(values.len(), base.len());
+    for (key, value) in keys.iter().zip(values.iter()) {
+        assert_eq!(base[key], *value);
+    }
+    let mut export = table.export();
+    let (mut chunked_keys, mut chunked_values) = (Vec::new(), Vec::new());
+    while export.next_chunk(100, &mut chunked_keys, &mut chunked_values) != 0 {}
+    assert_eq!(chunked_keys, keys);
+    assert_eq!(chunked_values, values);
+}
+
+#[test]
+fn export_unsized() {
+    let mut table = UnsizedHashtable::<[u8], u32>::new();
+    let mut base = HashMap::<Vec<u8>, u32>::new();
+    for _ in 0..10000 {
+        let mut rng = rand::thread_rng();
+        let mut key = vec![b'x'; rng.gen_range(0..40)];
+        if let Some(last) = key.last_mut() {
+            *last = rng.gen_range(0..4);
+        }
+        match unsafe { table.insert(&key) } {
+            Ok(x) => {
+                x.write(1);
+            }
+            Err(x) => {
+                *x += 1;
+            }
+        }
+        *base.entry(key).or_default() += 1;
+    }
+    let (mut offsets, mut data, mut values) = (Vec::<u32>::new(), Vec::new(), Vec::new());
+    table.export_keys(&mut offsets, &mut data);
+    table.export_values(&mut values);
+    assert_eq!(offsets.len(), base.len() + 1);
+    assert_eq!(values.len(), base.len());
+    for (i, value) in values.iter().enumerate() {
+        let key = &data[offsets[i] as usize..offsets[i + 1] as usize];
+        assert_eq!(base[key], *value);
+    }
+    let mut export = table.export();
+    let mut chunks = 0;
+    loop {
+        let (mut chunk_offsets, mut chunk_data) = (Vec::<u64>::new(), Vec::new());
+        let mut chunk_values = Vec::new();
+        let n = export.next_chunk(7, &mut chunk_offsets, &mut chunk_data, &mut chunk_values);
+        if n == 0 {
+            break;
+        }
+        assert!(n <= 7);
+        for (i, value) in chunk_values.iter().enumerate() {
+            let key = &chunk_data[chunk_offsets[i] as usize..chunk_offsets[i + 1] as usize];
+            assert_eq!(base[key], *value);
+        }
+        chunks += n;
+    }
+    assert_eq!(chunks, base.len());
+}
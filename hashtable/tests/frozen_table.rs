@@ -0,0 +1,57 @@
+use hashtable::hashtable::Hashtable;
+use hashtable::unsized_hashtable::UnsizedHashtable;
+use std::sync::Arc;
+
+#[test]
+fn frozen_hashtable() {
+    let mut table = Hashtable::<u64, u64>::with_capacity(1 << 20);
+    for i in 0..10000u64 {
+        unsafe {
+            table.insert(i).ok().unwrap().write(i * 2);
+        }
+    }
+    let frozen = Arc::new(table.freeze());
+    assert_eq!(frozen.len(), 10000);
+    assert_eq!(frozen.capacity(), 1 + (1 << 15));
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            let frozen = frozen.clone();
+            scope.spawn(move || {
+                for i in 0..20000u64 {
+                    if i < 10000 {
+                        assert_eq!(frozen.get(&i), Some(&(i * 2)));
+                    } else {
+                        assert_eq!(frozen.get(&i), None);
+                    }
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn frozen_unsized_hashtable() {
+    let keys = (0..10000u64)
+        .map(|i| format!("{:0>width$}", i, width = (i % 48) as usize))
+        .collect::<Vec<_>>();
+    let mut table = UnsizedHashtable::<str, u64>::new();
+    for (i, key) in keys.iter().enumerate() {
+        unsafe {
+            table.insert(key).ok().unwrap().write(i as u64);
+        }
+    }
+    let frozen = Arc::new(table.freeze());
+    assert_eq!(frozen.len(), keys.len());
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            let frozen = frozen.clone();
+            let keys = &keys;
+            scope.spawn(move || {
+                for (i, key) in keys.iter().enumerate() {
+                    assert_eq!(frozen.get(key), Some(&(i as u64)));
+                }
+                assert_eq!(frozen.get("missing key"), None);
+            });
+        }
+    });
+}
@@ -0,0 +1,50 @@
+use hashtable::group_index::{GroupIndex, UnsizedGroupIndex};
+use rand::Rng;
+use std::collections::HashMap;
+
+#[test]
+fn group_index() {
+    let mut sequence = vec![0u64; 1 << 16];
+    sequence.fill_with(|| rand::thread_rng().gen_range(0..1 << 10));
+    let mut index = GroupIndex::<u64>::new();
+    let mut base = HashMap::<u64, u32>::new();
+    for chunk in sequence.chunks(1000) {
+        let ids = index.insert_batch(chunk).to_vec();
+        for (key, id) in chunk.iter().zip(ids) {
+            let next = base.len() as u32;
+            assert_eq!(*base.entry(*key).or_insert(next), id);
+        }
+    }
+    assert_eq!(index.len(), base.len());
+    for (key, &id) in base.iter() {
+        assert_eq!(index.get(key), Some(id));
+        assert_eq!(index.key(id), key);
+    }
+    let mut first_seen = Vec::new();
+    for key in sequence.iter() {
+        if !first_seen.contains(key) {
+            first_seen.push(*key);
+        }
+    }
+    assert_eq!(index.keys(), &first_seen[..]);
+}
+
+#[test]
+fn unsized_group_index() {
+    let sequence = (0..10000u64)
+        .map(|i| format!("{:0>width$}", i % 777, width = (i % 37) as usize))
+        .collect::<Vec<_>>();
+    let keys = sequence.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+    let mut index = UnsizedGroupIndex::<str>::new();
+    let ids = index.insert_batch(&keys).to_vec();
+    let mut base = HashMap::<&str, u32>::new();
+    for (&key, id) in keys.iter().zip(ids) {
+        let next = base.len() as u32;
+        assert_eq!(*base.entry(key).or_insert(next), id);
+    }
+    assert_eq!(index.len(), base.len());
+    for (&key, &id) in base.iter() {
+        assert_eq!(index.get(key), Some(id));
+        assert_eq!(index.key(id), key);
+    }
+}
@@ -0,0 +1,132 @@
+use hashtable::hash_method::{ColumnData, DispatchedHashtable, HashMethod, KeyColumn, KeyRef};
+use hashtable::key_packing::{unpack, Column, ColumnMut, FixedColumn};
+use hashtable::row_key::{RowKeyReader, RowKeyWriter};
+use rand::Rng;
+use std::collections::HashMap;
+
+fn fixed(width: usize, nullable: bool) -> KeyColumn {
+    KeyColumn::Fixed(FixedColumn { width, nullable })
+}
+
+#[test]
+fn choose() {
+    let bytes = KeyColumn::Bytes { nullable: false };
+    let cases = [
+        (vec![fixed(1, false)], HashMethod::DirectMapped),
+        (vec![fixed(2, false)], HashMethod::DirectMapped),
+        (vec![fixed(1, true)], HashMethod::Keys16),
+        (vec![fixed(4, false)], HashMethod::Keys32),
+        (vec![fixed(2, false), fixed(2, true)], HashMethod::Keys64),
+        (vec![fixed(8, false), fixed(8, true)], HashMethod::Keys256),
+        (vec![fixed(8, false); 4], HashMethod::Keys256),
+        (vec![fixed(8, false); 5], HashMethod::Serialized),
+        (vec![bytes], HashMethod::Bytes),
+        (
+            vec![KeyColumn::Bytes { nullable: true }],
+            HashMethod::Serialized,
+        ),
+        (vec![bytes, fixed(1, false)], HashMethod::Serialized),
+    ];
+    for (schema, method) in cases {
+        assert_eq!(HashMethod::choose(&schema), method);
+        assert_eq!(DispatchedHashtable::<u32>::new(&schema).method(), method);
+    }
+}
+
+fn count(table: &mut DispatchedHashtable<u32>, columns: &[ColumnData<'_>]) {
+    unsafe {
+        table.insert_batch(columns, |_, result| match result {
+            Ok(x) => {
+                x.write(1);
+            }
+            Err(x) => {
+                *x += 1;
+            }
+        });
+    }
+}
+
+#[test]
+fn dispatched() {
+    let n = 10000;
+    let mut a = vec![0u16; n];
+    a.fill_with(|| rand::thread_rng().gen_range(0..300));
+    let mut b = vec![0u32; n];
+    b.fill_with(|| rand::thread_rng().gen_range(0..3));
+    let mut validity = vec![0u8; (n + 7) / 8];
+    validity.fill_with(|| rand::thread_rng().gen());
+    let strings = (0..n).map(|i| format!("{}", i % 37)).collect::<Vec<_>>();
+    let c = strings.iter().map(|x| x.as_bytes()).collect::<Vec<_>>();
+    let valid = |i: usize| validity[i / 8] & (1 << (i % 8)) != 0;
+
+    let columns = [ColumnData::Fixed(Column::new(&a))];
+    let mut table = DispatchedHashtable::<u32>::new(&[columns[0].describe()]);
+    assert_eq!(table.method(), HashMethod::DirectMapped);
+    count(&mut table, &columns);
+    let mut base = HashMap::<u16, u32>::new();
+    for &x in a.iter() {
+        *base.entry(x).or_default() += 1;
+    }
+    assert_eq!(table.len(), base.len());
+    for (key, &value) in table.iter() {
+        let KeyRef::Keys16(key) = key else { panic!() };
+        assert_eq!(base[&key], value);
+    }
+
+    let columns = [
+        ColumnData::Fixed(Column::new(&a)),
+        ColumnData::Fixed(Column::new(&b).with_validity(&validity)),
+    ];
+    let schema = columns.iter().map(ColumnData::describe).collect::<Vec<_>>();
+    let mut table = DispatchedHashtable::<u32>::new(&schema);
+    assert_eq!(table.method(), HashMethod::Keys64);
+    count(&mut table, &columns);
+    let mut base = HashMap::<(u16, Option<u32>), u32>::new();
+    for i in 0..n {
+        *base.entry((a[i], valid(i).then(|| b[i]))).or_default() += 1;
+    }
+    assert_eq!(table.len(), base.len());
+    for (key, &value) in table.iter() {
+        let KeyRef::Keys64(key) = key else { panic!() };
+        let (mut x, mut y, mut z) = ([0u16], [0u32], [0u8]);
+        unpack(
+            &[key],
+            &mut [
+                ColumnMut::new(&mut x),
+                ColumnMut::new(&mut y).with_validity(&mut z),
+            ],
+        );
+        assert_eq!(base[&(x[0], (z[0] != 0).then(|| y[0]))], value);
+    }
+
+    let columns = [
+        ColumnData::Bytes {
+            values: &c,
+            validity: Some(&validity),
+        },
+        ColumnData::Fixed(Column::new(&b)),
+    ];
+    let schema = columns.iter().map(ColumnData::describe).collect::<Vec<_>>();
+    let mut table = DispatchedHashtable::<u32>::new(&schema);
+    assert_eq!(table.method(), HashMethod::Serialized);
+    count(&mut table, &columns);
+    let mut base = HashMap::<(Option<&[u8]>, u32), u32>::new();
+    for i in 0..n {
+        *base.entry((valid(i).then(|| c[i]), b[i])).or_default() += 1;
+    }
+    assert_eq!(table.len(), base.len());
+    for (key, &value) in table.iter() {
+        let KeyRef::Bytes(key) = key else { panic!() };
+        let mut reader = RowKeyReader::new(key);
+        let row = (reader.read_nullable_bytes(), reader.read_fixed::<u32>());
+        assert_eq!(base[&row], value);
+    }
+    let mut hits = 0;
+    let mut scratch = RowKeyWriter::new();
+    table.get_batch(&columns, &mut scratch, |i, value| {
+        let row = (valid(i).then(|| c[i]), b[i]);
+        assert_eq!(value.copied(), Some(base[&row]));
+        hits += 1;
+    });
+    assert_eq!(hits, n);
+}
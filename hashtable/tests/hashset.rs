@@ -0,0 +1,82 @@
+use hashtable::hashset::{Hashset, TwolevelHashset, UnsizedHashset};
+use std::collections::HashSet;
+
+#[test]
+fn hashset_algebra() {
+    let mut a = Hashset::<u64>::new();
+    let mut b = Hashset::<u64>::new();
+    for i in 0..10000u64 {
+        assert!(a.insert(i * 2));
+        assert!(!a.insert(i * 2));
+        b.insert(i * 3);
+    }
+    assert!(a.contains(&0));
+    assert!(!a.contains(&1));
+    let intersection = a.intersection(&b).into_iter().collect::<HashSet<_>>();
+    let expected = (0..20000u64).filter(|x| x % 6 == 0).collect::<HashSet<_>>();
+    assert_eq!(intersection, expected);
+    let difference = a.difference(&b).into_iter().collect::<HashSet<_>>();
+    let expected = (0..20000u64)
+        .filter(|x| x % 2 == 0 && x % 6 != 0)
+        .collect::<HashSet<_>>();
+    assert_eq!(difference, expected);
+    a.union(b);
+    assert_eq!(a.len(), 10000 + 10000 - 3334);
+}
+
+#[test]
+fn twolevel_hashset_parallel_union() {
+    let sets = std::thread::scope(|scope| {
+        let handles = (0..4u64)
+            .map(|t| {
+                scope.spawn(move || {
+                    let mut set = TwolevelHashset::<u64>::new();
+                    for i in 0..100000u64 {
+                        set.insert(i * (t + 1));
+                    }
+                    set
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|x| x.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    let mut base = HashSet::new();
+    for t in 0..4u64 {
+        for i in 0..100000u64 {
+            base.insert(i * (t + 1));
+        }
+    }
+    let mut sets = sets.into_iter();
+    let mut result = sets.next().unwrap();
+    for set in sets {
+        result.union(set);
+    }
+    assert_eq!(result.len(), base.len());
+    assert!(result.iter().all(|x| base.contains(x)));
+}
+
+#[test]
+fn unsized_hashset_algebra() {
+    let mut a = UnsizedHashset::<str>::new();
+    let mut b = UnsizedHashset::<str>::new();
+    let keys = (0..1000u64)
+        .map(|i| format!("{:0>width$}", i, width = (i % 40) as usize))
+        .collect::<Vec<_>>();
+    for (i, key) in keys.iter().enumerate() {
+        if i % 2 == 0 {
+            assert!(a.insert(key));
+        }
+        if i % 3 == 0 {
+            assert!(b.insert(key));
+        }
+    }
+    assert_eq!(a.intersection(&b).len(), 167);
+    assert_eq!(a.difference(&b).len(), 500 - 167);
+    a.union(b);
+    assert_eq!(a.len(), 500 + 334 - 167);
+    assert!(a.contains(&keys[3]));
+    assert!(!a.contains(&keys[5]));
+}
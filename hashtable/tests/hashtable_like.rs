@@ -0,0 +1,118 @@
+use hashtable::experimental::extendible_hashtable::ExtendibleHashtable;
+use hashtable::experimental::stack_hashtable::StackHashtable;
+use hashtable::hashtable::Hashtable;
+use hashtable::traits::HashtableLike;
+use hashtable::twolevel_hashtable::TwolevelHashtable;
+use hashtable::twolevel_unsized_hashtable::TwolevelUnsizedHashtable;
+use hashtable::unsized_hashtable::UnsizedHashtable;
+use rand::Rng;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+fn count<T, K, Q>(table: &mut T, keys: &[K])
+where
+    T: HashtableLike<Key = Q, Value = u64>,
+    K: Borrow<Q>,
+    Q: ?Sized,
+{
+    for key in keys.iter() {
+        match unsafe { table.insert(key.borrow()) } {
+            Ok(x) => {
+                x.write(1);
+            }
+            Err(x) => {
+                *x += 1;
+            }
+        }
+    }
+}
+
+/// Counts halves of `keys` in two tables, and merges them.
+fn count_merged<T, K, Q>(mut table: T, mut other: T, keys: &[K]) -> T
+where
+    T: HashtableLike<Key = Q, Value = u64>,
+    K: Borrow<Q>,
+    Q: ?Sized,
+{
+    count(&mut table, &keys[..keys.len() / 2]);
+    count(&mut other, &keys[keys.len() / 2..]);
+    unsafe {
+        table.merge(other, |_, result, value| match result {
+            Ok(x) => {
+                x.write(value);
+            }
+            Err(x) => {
+                *x += value;
+            }
+        });
+    }
+    table
+}
+
+fn check<T, K>(table: &mut T, keys: &[K])
+where
+    T: HashtableLike<Value = u64>,
+    T::Key: Eq + Hash,
+    K: Borrow<T::Key>,
+{
+    let mut base = HashMap::<&T::Key, u64>::new();
+    for key in keys.iter() {
+        *base.entry(key.borrow()).or_default() += 1;
+    }
+    assert_eq!(table.len(), base.len());
+    assert!(!table.is_empty());
+    for (key, value) in table.iter() {
+        assert_eq!(base[key], *value);
+    }
+    for (_, value) in table.iter_mut() {
+        *value *= 2;
+    }
+    for (key, value) in base.iter() {
+        assert_eq!(table.get(key), Some(&(value * 2)));
+        *table.get_mut(key).unwrap() = 0;
+    }
+    assert!(table.iter().all(|(_, value)| *value == 0));
+}
+
+#[test]
+fn hashtable_like() {
+    let mut keys = vec![0u64; 1 << 16];
+    keys.fill_with(|| rand::thread_rng().gen_range(0..1 << 12));
+    let mut table = count_merged(Hashtable::<u64, u64>::new(), Hashtable::new(), &keys);
+    check(&mut table, &keys);
+    let mut table = count_merged(
+        TwolevelHashtable::<u64, u64>::new(),
+        TwolevelHashtable::new(),
+        &keys,
+    );
+    check(&mut table, &keys);
+    let mut table = count_merged(
+        StackHashtable::<u64, u64>::new(),
+        StackHashtable::new(),
+        &keys,
+    );
+    check(&mut table, &keys);
+    let mut table = count_merged(
+        ExtendibleHashtable::<u64, u64>::new(),
+        ExtendibleHashtable::new(),
+        &keys,
+    );
+    check(&mut table, &keys);
+    let keys = keys
+        .iter()
+        .map(|x| "x".repeat((*x % 40) as usize))
+        .collect::<Vec<_>>();
+    let mut table = count_merged(
+        UnsizedHashtable::<str, u64>::new(),
+        UnsizedHashtable::new(),
+        &keys,
+    );
+    check(&mut table, &keys);
+    let mut table = count_merged(
+        TwolevelUnsizedHashtable::<str, u64>::new(),
+        TwolevelUnsizedHashtable::new(),
+        &keys,
+    );
+    check(&mut table, &keys);
+}
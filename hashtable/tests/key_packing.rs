@@ -0,0 +1,95 @@
+use hashtable::hashtable::Hashtable;
+use hashtable::key_packing::{pack, packed_size, unpack, Column, ColumnMut, FixedColumn};
+use primitive_types::U256;
+use rand::Rng;
+use std::collections::HashMap;
+
+#[test]
+fn pack_u64() {
+    let n = 1 << 16;
+    let mut a = vec![0u8; n];
+    a.fill_with(|| rand::thread_rng().gen_range(0..16));
+    let mut b = vec![0i16; n];
+    b.fill_with(|| rand::thread_rng().gen_range(-8..8));
+    let mut c = vec![0u32; n];
+    c.fill_with(|| rand::thread_rng().gen_range(0..4));
+    let columns = [Column::new(&a), Column::new(&b), Column::new(&c)];
+    let mut keys = vec![0u64; n];
+    pack(&columns, &mut keys);
+    let mut table = Hashtable::<u64, u32>::new();
+    for &key in keys.iter() {
+        match unsafe { table.insert(key) } {
+            Ok(x) => {
+                x.write(1);
+            }
+            Err(x) => {
+                *x += 1;
+            }
+        }
+    }
+    let mut base = HashMap::<(u8, i16, u32), u32>::new();
+    for i in 0..n {
+        *base.entry((a[i], b[i], c[i])).or_default() += 1;
+    }
+    assert_eq!(table.len(), base.len());
+    let (groups, counts): (Vec<u64>, Vec<u32>) = table.iter().map(|(k, v)| (*k, *v)).unzip();
+    let mut x = vec![0u8; groups.len()];
+    let mut y = vec![0i16; groups.len()];
+    let mut z = vec![0u32; groups.len()];
+    unpack(
+        &groups,
+        &mut [
+            ColumnMut::new(&mut x),
+            ColumnMut::new(&mut y),
+            ColumnMut::new(&mut z),
+        ],
+    );
+    for i in 0..groups.len() {
+        assert_eq!(base[&(x[i], y[i], z[i])], counts[i]);
+    }
+}
+
+#[test]
+fn pack_nullable() {
+    let n = 1000;
+    let a = (0..n as u64).map(|i| i * 7).collect::<Vec<_>>();
+    let b = (0..n as i64).map(|i| -i).collect::<Vec<_>>();
+    let mut validity = vec![0u8; (n + 7) / 8];
+    validity.fill_with(|| rand::thread_rng().gen());
+    let columns = [Column::new(&a).with_validity(&validity), Column::new(&b)];
+    assert_eq!(
+        packed_size(&columns.iter().map(Column::describe).collect::<Vec<_>>()),
+        17
+    );
+    assert_eq!(
+        columns[0].describe(),
+        FixedColumn {
+            width: 8,
+            nullable: true
+        }
+    );
+    let mut keys = vec![U256::zero(); n];
+    pack(&columns, &mut keys);
+    let mut narrow = vec![0u128; n];
+    let mut x = vec![0u64; n];
+    let mut y = vec![0i64; n];
+    let mut unpacked = vec![0u8; (n + 7) / 8];
+    unpack(
+        &keys,
+        &mut [
+            ColumnMut::new(&mut x).with_validity(&mut unpacked),
+            ColumnMut::new(&mut y),
+        ],
+    );
+    assert_eq!(validity, unpacked);
+    for i in 0..n {
+        if validity[i / 8] & (1 << (i % 8)) != 0 {
+            assert_eq!(x[i], a[i]);
+        } else {
+            assert_eq!(x[i], 0);
+        }
+        assert_eq!(y[i], b[i]);
+    }
+    let result = std::panic::catch_unwind(move || pack(&columns, &mut narrow));
+    assert!(result.is_err());
+}
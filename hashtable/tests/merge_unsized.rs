@@ -0,0 +1,59 @@
+use hashtable::unsized_hashtable::UnsizedHashtable;
+use rand::Rng;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+type Table = UnsizedHashtable<[u8], (u64, Rc<()>)>;
+
+fn build(marker: &Rc<()>) -> (Table, HashMap<Vec<u8>, u64>) {
+    let mut table = Table::new();
+    let mut base = HashMap::<Vec<u8>, u64>::new();
+    for _ in 0..10000 {
+        let mut rng = rand::thread_rng();
+        let mut key = vec![b'x'; rng.gen_range(0..50)];
+        if let Some(last) = key.last_mut() {
+            *last = rng.gen_range(0..8);
+        }
+        match unsafe { table.insert(&key) } {
+            Ok(x) => {
+                x.write((1, marker.clone()));
+            }
+            Err(x) => {
+                x.0 += 1;
+            }
+        }
+        *base.entry(key).or_default() += 1;
+    }
+    (table, base)
+}
+
+#[test]
+fn merge_unsized() {
+    let marker = Rc::new(());
+    let (mut table, mut base) = build(&marker);
+    let (other, other_base) = build(&marker);
+    assert_eq!(Rc::strong_count(&marker), 1 + table.len() + other.len());
+    for (key, count) in other_base {
+        *base.entry(key).or_default() += count;
+    }
+    unsafe {
+        table.merge(other, |_, result, value| match result {
+            Ok(x) => {
+                x.write(value);
+            }
+            Err(x) => {
+                x.0 += value.0;
+            }
+        });
+    }
+    assert_eq!(table.len(), base.len());
+    assert_eq!(Rc::strong_count(&marker), 1 + table.len());
+    for (key, (count, _)) in table.iter() {
+        assert_eq!(base[key], *count);
+    }
+    for (key, count) in base.iter() {
+        assert_eq!(table.get(key).map(|x| x.0), Some(*count));
+    }
+    drop(table);
+    assert_eq!(Rc::strong_count(&marker), 1);
+}
@@ -0,0 +1,87 @@
+use hashtable::multimap::{HashMultimap, UnsizedHashMultimap};
+use rand::Rng;
+use std::collections::HashMap;
+
+#[test]
+fn multimap_probe() {
+    let mut build = vec![0u64; 1 << 16];
+    build.fill_with(|| rand::thread_rng().gen_range(0..1 << 12));
+    let mut probe = vec![0u64; 1 << 12];
+    probe.fill_with(|| rand::thread_rng().gen_range(0..1 << 13));
+    let mut base = HashMap::<u64, Vec<u32>>::new();
+    for (i, &key) in build.iter().enumerate() {
+        base.entry(key).or_default().push(i as u32);
+    }
+    let mut multimap = HashMultimap::<u64>::new();
+    multimap.insert_batch(&build, 0);
+    assert_eq!(multimap.len(), base.len());
+    assert_eq!(multimap.rows(), build.len());
+    let mut probe_rows = Vec::new();
+    let mut build_rows = Vec::new();
+    let count = multimap.probe(&probe, &mut probe_rows, &mut build_rows);
+    assert_eq!(count, build_rows.len());
+    assert_eq!(count, probe_rows.len());
+    let expected = probe
+        .iter()
+        .map(|key| base.get(key).map(|x| x.len()).unwrap_or(0))
+        .sum::<usize>();
+    assert_eq!(count, expected);
+    for (&i, &row) in probe_rows.iter().zip(build_rows.iter()) {
+        assert_eq!(probe[i as usize], build[row as usize]);
+    }
+}
+
+#[test]
+fn unsized_multimap_probe() {
+    let build = (0..10000u32)
+        .map(|i| format!("{:0>width$}", i % 1000, width = (i % 40) as usize))
+        .collect::<Vec<_>>();
+    let mut multimap = UnsizedHashMultimap::<str>::new();
+    multimap.insert_batch(&build.iter().map(|x| x.as_str()).collect::<Vec<_>>(), 0);
+    assert_eq!(multimap.rows(), build.len());
+    let probe = ["0000000000000000000000000000000000000007", "42", "missing"];
+    let mut probe_rows = Vec::new();
+    let mut build_rows = Vec::new();
+    let count = multimap.probe(&probe, &mut probe_rows, &mut build_rows);
+    let expected = probe
+        .iter()
+        .map(|&key| build.iter().filter(|&x| x == key).count())
+        .sum::<usize>();
+    assert_eq!(count, expected);
+    for (&i, &row) in probe_rows.iter().zip(build_rows.iter()) {
+        assert_eq!(probe[i as usize], build[row as usize]);
+    }
+}
+
+#[test]
+fn multimap_match_tracking() {
+    let build = (0..100000u64).map(|i| i % 5000).collect::<Vec<_>>();
+    let mut multimap = HashMultimap::<u64>::new();
+    multimap.insert_batch(&build[..50000], 0);
+    multimap.enable_match_tracking();
+    multimap.insert_batch(&build[50000..], 50000);
+    let probe = (0..10000u64).filter(|x| x % 3 == 0).collect::<Vec<_>>();
+    std::thread::scope(|scope| {
+        for chunk in probe.chunks(probe.len() / 4 + 1) {
+            let multimap = &multimap;
+            scope.spawn(move || {
+                let mut probe_rows = Vec::new();
+                let mut build_rows = Vec::new();
+                multimap.probe(chunk, &mut probe_rows, &mut build_rows);
+            });
+        }
+    });
+    let mut matched = multimap.iter_matched().collect::<Vec<_>>();
+    matched.sort_unstable();
+    let expected = (0..build.len() as u32)
+        .filter(|&i| build[i as usize] % 3 == 0)
+        .collect::<Vec<_>>();
+    assert_eq!(matched, expected);
+    assert!(multimap
+        .iter_unmatched()
+        .all(|row| build[row as usize] % 3 != 0));
+    assert_eq!(
+        multimap.iter_unmatched().count() + multimap.iter_matched().count(),
+        build.len()
+    );
+}
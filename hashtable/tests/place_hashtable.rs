@@ -0,0 +1,93 @@
+use hashtable::place_hashtable::{PlaceHashtable, StateLayout, UnsizedPlaceHashtable};
+use rand::Rng;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+#[repr(align(64))]
+struct Sum(u64);
+
+#[test]
+fn place_hashtable() {
+    let mut sequence = vec![0u64; 1 << 16];
+    sequence.fill_with(|| rand::thread_rng().gen_range(0..1 << 12));
+    let mut table = PlaceHashtable::<u64>::new(StateLayout::of::<Sum>());
+    let mut first = HashMap::<u64, NonNull<u8>>::new();
+    for chunk in sequence.chunks(1000) {
+        let places = table.insert_batch(chunk, |place| unsafe {
+            place.cast::<Sum>().as_ptr().write(Sum(0));
+        });
+        for (&key, &place) in chunk.iter().zip(places) {
+            assert_eq!(place.as_ptr() as usize % 64, 0);
+            assert_eq!(*first.entry(key).or_insert(place), place);
+            unsafe { place.cast::<Sum>().as_mut().0 += key };
+        }
+    }
+    let mut base = HashMap::<u64, u64>::new();
+    for &key in sequence.iter() {
+        *base.entry(key).or_default() += key;
+    }
+    assert_eq!(table.len(), base.len());
+    for (key, place) in table.iter() {
+        assert_eq!(unsafe { place.cast::<Sum>().as_ref().0 }, base[key]);
+        assert_eq!(first[key], place);
+    }
+}
+
+#[test]
+fn unsized_place_hashtable_drop() {
+    let counter = Rc::new(Cell::new(0usize));
+    struct State(Rc<Cell<usize>>);
+    impl Drop for State {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+    let sequence = (0..10000u64)
+        .map(|i| format!("{:0>width$}", i % 777, width = (i % 37) as usize))
+        .collect::<Vec<_>>();
+    let keys = sequence.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+    let len;
+    {
+        let mut table = UnsizedPlaceHashtable::<str>::new(StateLayout::of::<State>());
+        table.insert_batch(&keys, |place| unsafe {
+            place.cast::<State>().as_ptr().write(State(counter.clone()));
+        });
+        len = table.len();
+        for &key in keys.iter() {
+            assert!(table.get(key).is_some());
+        }
+        assert_eq!(counter.get(), 0);
+    }
+    assert_eq!(counter.get(), len);
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
+
+#[test]
+fn place_hashtable_init_panic() {
+    let counter = Rc::new(Cell::new(0usize));
+    struct State(Rc<Cell<usize>>);
+    impl Drop for State {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+    let keys = (0..100u64).collect::<Vec<_>>();
+    let mut table = PlaceHashtable::<u64>::new(StateLayout::of::<State>());
+    let mut initialized = 0usize;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        table.insert_batch(&keys, |place| unsafe {
+            if initialized == 50 {
+                panic!();
+            }
+            place.cast::<State>().as_ptr().write(State(counter.clone()));
+            initialized += 1;
+        });
+    }));
+    assert!(result.is_err());
+    assert_eq!(table.len(), 50);
+    assert!(table.get(&50).is_none());
+    drop(table);
+    assert_eq!(counter.get(), 50);
+}
@@ -0,0 +1,79 @@
+use hashtable::row_key::{RowKeyReader, RowKeyWriter};
+use hashtable::unsized_hashtable::UnsizedHashtable;
+use rand::Rng;
+use std::collections::HashMap;
+
+type Row = (u32, Option<i64>, String, Option<String>);
+
+#[test]
+fn row_key() {
+    let mut rows = Vec::<Row>::new();
+    for _ in 0..10000 {
+        let mut rng = rand::thread_rng();
+        let len = if rng.gen_bool(0.01) {
+            300
+        } else {
+            rng.gen_range(0..4)
+        };
+        rows.push((
+            rng.gen_range(0..8),
+            rng.gen_bool(0.5).then(|| rng.gen_range(-2..2)),
+            "x".repeat(len),
+            rng.gen_bool(0.5).then(|| "y".repeat(rng.gen_range(0..3))),
+        ));
+    }
+    let mut writer: RowKeyWriter = RowKeyWriter::new();
+    let mut table = UnsizedHashtable::<[u8], u32>::new();
+    let mut base = HashMap::<Row, u32>::new();
+    for chunk in rows.chunks(1000) {
+        writer.clear();
+        for (a, b, c, d) in chunk.iter() {
+            writer.write_fixed(*a);
+            writer.write_nullable_fixed(*b);
+            writer.write_str(c);
+            writer.write_nullable_str(d.as_deref());
+            writer.finish_row();
+        }
+        assert_eq!(writer.len(), chunk.len());
+        for (key, row) in writer.keys().zip(chunk.iter()) {
+            if key.len() <= 24 {
+                assert_ne!(key.last().copied(), Some(0));
+            }
+            match unsafe { table.insert(key) } {
+                Ok(x) => {
+                    x.write(1);
+                }
+                Err(x) => {
+                    *x += 1;
+                }
+            }
+            *base.entry(row.clone()).or_default() += 1;
+        }
+    }
+    assert_eq!(table.len(), base.len());
+    for (key, count) in table.iter() {
+        let mut reader = RowKeyReader::new(key);
+        let row = (
+            reader.read_fixed::<u32>(),
+            reader.read_nullable_fixed::<i64>(),
+            reader.read_str().to_string(),
+            reader.read_nullable_str().map(str::to_string),
+        );
+        assert_eq!(base[&row], *count);
+    }
+}
+
+#[test]
+fn row_key_lengths() {
+    let mut writer: RowKeyWriter = RowKeyWriter::new();
+    let long = vec![7u8; 1000];
+    writer.write_bytes(&long);
+    writer.write_fixed(0u8);
+    writer.finish_row();
+    writer.finish_row();
+    assert_eq!(writer.key(0).len(), 2 + 1000 + 1 + 1);
+    assert!(writer.key(1).is_empty());
+    let mut reader = RowKeyReader::new(writer.key(0));
+    assert_eq!(reader.read_bytes(), &long[..]);
+    assert_eq!(reader.read_fixed::<u8>(), 0);
+}
@@ -0,0 +1,54 @@
+use hashtable::concurrent_hashtable::ConcurrentHashtable;
+use hashtable::experimental::extendible_hashtable::ExtendibleHashtable;
+use hashtable::experimental::stack_hashtable::StackHashtable;
+use hashtable::hashtable::Hashtable;
+use hashtable::twolevel_hashtable::TwolevelHashtable;
+use hashtable::twolevel_unsized_hashtable::TwolevelUnsizedHashtable;
+use hashtable::unsized_hashtable::UnsizedHashtable;
+
+fn assert_send<T: Send>() {}
+
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn send_sync() {
+    assert_send::<Hashtable<u64, u64>>();
+    assert_sync::<Hashtable<u64, u64>>();
+    assert_send::<TwolevelHashtable<u64, u64>>();
+    assert_sync::<TwolevelHashtable<u64, u64>>();
+    assert_send::<ConcurrentHashtable<u64, u64>>();
+    assert_sync::<ConcurrentHashtable<u64, u64>>();
+    assert_send::<StackHashtable<u64, u64>>();
+    assert_sync::<StackHashtable<u64, u64>>();
+    assert_send::<ExtendibleHashtable<u64, u64>>();
+    assert_sync::<ExtendibleHashtable<u64, u64>>();
+    assert_send::<UnsizedHashtable<[u8], u64>>();
+    assert_sync::<UnsizedHashtable<[u8], u64>>();
+    assert_send::<UnsizedHashtable<str, u64>>();
+    assert_sync::<UnsizedHashtable<str, u64>>();
+    assert_send::<TwolevelUnsizedHashtable<[u8], u64>>();
+    assert_sync::<TwolevelUnsizedHashtable<[u8], u64>>();
+}
+
+#[test]
+fn unsized_probe_in_threads() {
+    let mut table = UnsizedHashtable::<[u8], u64>::new();
+    let keys = (0..10000u64)
+        .map(|i| format!("{:0>width$}", i, width = (i % 64) as usize).into_bytes())
+        .collect::<Vec<_>>();
+    for (i, key) in keys.iter().enumerate() {
+        unsafe {
+            table.insert(key).ok().unwrap().write(i as u64);
+        }
+    }
+    let table = std::thread::spawn(move || table).join().unwrap();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for (i, key) in keys.iter().enumerate() {
+                    assert_eq!(table.get(key), Some(&(i as u64)));
+                }
+            });
+        }
+    });
+}
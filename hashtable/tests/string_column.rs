@@ -0,0 +1,101 @@
+use hashtable::group_index::{UnsizedGroupIndex, NULL_GROUP};
+use hashtable::string_column::StringColumn;
+use hashtable::unsized_hashtable::UnsizedHashtable;
+use rand::Rng;
+use std::collections::HashMap;
+
+fn build(n: usize) -> (Vec<Vec<u8>>, Vec<u8>) {
+    let mut rng = rand::thread_rng();
+    let values = (0..n)
+        .map(|_| {
+            let len = match rng.gen_range(0..4) {
+                0 => rng.gen_range(0..3),
+                1 => rng.gen_range(3..25),
+                2 => rng.gen_range(25..40),
+                _ => 300,
+            };
+            let mut value = vec![b'x'; len];
+            if len != 0 {
+                value[len - 1] = rng.gen_range(0..4);
+            }
+            value
+        })
+        .collect::<Vec<_>>();
+    let mut validity = vec![0u8; (n + 7) / 8];
+    validity.fill_with(|| rng.gen());
+    (values, validity)
+}
+
+#[test]
+fn string_column() {
+    let n = 10000;
+    let (values, validity) = build(n);
+    let valid = |i: usize| validity[i / 8] & (1 << (i % 8)) != 0;
+    let data = values.concat();
+    let mut offsets = vec![0u64];
+    for value in values.iter() {
+        offsets.push(offsets.last().unwrap() + value.len() as u64);
+    }
+    let column = StringColumn::new(&offsets, &data).with_validity(&validity);
+    assert_eq!(column.len(), n);
+    let mut table = UnsizedHashtable::<[u8], u32>::new();
+    unsafe {
+        table.insert_column(&column, |i, result| {
+            assert!(valid(i));
+            match result {
+                Ok(x) => {
+                    x.write(1);
+                }
+                Err(x) => {
+                    *x += 1;
+                }
+            }
+        });
+    }
+    let mut base = HashMap::<&[u8], u32>::new();
+    for (i, value) in values.iter().enumerate() {
+        if valid(i) {
+            *base.entry(value).or_default() += 1;
+        }
+    }
+    assert_eq!(table.len(), base.len());
+    for (key, value) in table.iter() {
+        assert_eq!(base[key], *value);
+    }
+    let column = StringColumn::new(&offsets, &data);
+    let mut hits = 0;
+    table.get_column(&column, |i, value| {
+        assert_eq!(value.copied(), base.get(&values[i][..]).copied());
+        hits += 1;
+    });
+    assert_eq!(hits, n);
+}
+
+#[test]
+fn string_column_group_ids() {
+    let n = 10000;
+    let (values, validity) = build(n);
+    let valid = |i: usize| validity[i / 8] & (1 << (i % 8)) != 0;
+    let data = values.concat();
+    let mut offsets = vec![0u32];
+    for value in values.iter() {
+        offsets.push(offsets.last().unwrap() + value.len() as u32);
+    }
+    let column = StringColumn::new(&offsets, &data).with_validity(&validity);
+    let mut index = UnsizedGroupIndex::<[u8]>::new();
+    let ids = index.insert_column(&column).to_vec();
+    assert_eq!(ids.len(), n);
+    for (i, &id) in ids.iter().enumerate() {
+        if valid(i) {
+            assert_eq!(index.key(id), &values[i][..]);
+        } else {
+            assert_eq!(id, NULL_GROUP);
+        }
+    }
+    let ids = index
+        .insert_column(&StringColumn::new(&offsets, &data))
+        .to_vec();
+    for (i, &id) in ids.iter().enumerate() {
+        assert_eq!(index.key(id), &values[i][..]);
+    }
+}
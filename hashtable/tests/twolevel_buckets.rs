@@ -0,0 +1,123 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::Tracker;
+use hashtable::hashtable::Hashtable;
+use hashtable::twolevel_hashtable::TwolevelHashtable;
+use rand::Rng;
+use std::collections::HashMap;
+
+#[test]
+fn twolevel_buckets() {
+    let mut sequence = vec![0u64; 1 << 20];
+    sequence.fill_with(|| rand::thread_rng().gen_range(0..1 << 16));
+    let mut base = HashMap::<u64, u64>::new();
+    let mut other = TwolevelHashtable::<u64, u64>::new();
+    for &s in sequence.iter() {
+        *base.entry(s).or_default() += 1;
+        match unsafe { other.insert(s) } {
+            Ok(x) => {
+                x.write(1);
+            }
+            Err(x) => {
+                *x += 1;
+            }
+        }
+    }
+    assert_eq!(base.len(), other.len());
+    assert_eq!(
+        (0..other.bucket_count())
+            .map(|i| other.bucket(i).len())
+            .sum::<usize>(),
+        other.len()
+    );
+    for i in 0..other.bucket_count() {
+        for (key, value) in other.bucket(i).iter() {
+            assert_eq!(other.get(key), Some(value));
+        }
+    }
+    let mut count = 0usize;
+    let iter = other.into_iter();
+    assert_eq!(iter.len(), base.len());
+    for (key, value) in iter {
+        assert_eq!(base.get(&key), Some(&value));
+        count += 1;
+    }
+    assert_eq!(base.len(), count);
+}
+
+#[test]
+fn twolevel_into_buckets() {
+    let mut other = TwolevelHashtable::<u64, u64>::new();
+    for s in 0..100000u64 {
+        unsafe {
+            other.insert(s).ok().unwrap().write(s);
+        }
+    }
+    let mut count = 0usize;
+    for bucket in other.into_buckets() {
+        for (key, value) in bucket.into_iter() {
+            assert_eq!(key, value);
+            count += 1;
+        }
+    }
+    assert_eq!(count, 100000);
+}
+
+#[test]
+fn twolevel_lazy_buckets() {
+    let tracker = Tracker::default();
+    let mut table = TwolevelHashtable::<u64, u64, _>::new_in(tracker.clone());
+    assert_eq!(table.capacity(), 0);
+    assert_eq!(tracker.allocated(), 0);
+    assert_eq!(table.get(&0), None);
+    assert_eq!(table.get(&1), None);
+    assert_eq!(table.iter().count(), 0);
+    unsafe {
+        table.insert(1).ok().unwrap().write(1);
+    }
+    let allocated = (0..table.bucket_count())
+        .filter(|&i| table.bucket(i).capacity() > 0)
+        .count();
+    assert_eq!(allocated, 1);
+    let mut other = TwolevelHashtable::<u64, u64, _>::new_in(tracker.clone());
+    for s in 0..1000u64 {
+        unsafe {
+            other.insert(s).ok().unwrap().write(1);
+        }
+    }
+    unsafe {
+        table.merge(other, |_, result, value| match result {
+            Ok(x) => {
+                x.write(value);
+            }
+            Err(x) => {
+                *x += value;
+            }
+        });
+    }
+    assert_eq!(table.len(), 1000);
+    assert_eq!(table.get(&1), Some(&2));
+    assert_eq!(table.get(&0), Some(&1));
+}
+
+#[test]
+fn hashtable_zero_key() {
+    let mut table = Hashtable::<u64, u64>::new();
+    for s in 0..100u64 {
+        unsafe {
+            table.insert(s).ok().unwrap().write(s);
+        }
+    }
+    assert_eq!(table.iter().last(), Some((&0, &0)));
+    for (_, value) in table.iter_mut() {
+        *value += 1;
+    }
+    assert_eq!(table.get(&0), Some(&1));
+    let iter = table.into_iter();
+    assert_eq!(iter.size_hint(), (100, Some(100)));
+    let mut entries = iter.collect::<Vec<_>>();
+    entries.sort_unstable();
+    assert_eq!(entries, (0..100u64).map(|s| (s, s + 1)).collect::<Vec<_>>());
+}
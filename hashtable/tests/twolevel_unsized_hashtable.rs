@@ -0,0 +1,95 @@
+#![feature(allocator_api)]
+
+mod common;
+
+use common::{insert_random_keys, Tracker};
+use hashtable::twolevel_unsized_hashtable::TwolevelUnsizedHashtable;
+use hashtable::unsized_hashtable::key_hash;
+use std::collections::HashMap;
+use std::mem::MaybeUninit;
+
+type Table = TwolevelUnsizedHashtable<[u8], u64, Tracker>;
+
+fn build(tracker: &Tracker, n: usize) -> (Table, HashMap<Vec<u8>, u64>) {
+    let mut table = Table::new_in(tracker.clone());
+    let base = insert_random_keys(&mut table, n, || 1, |x| *x += 1);
+    (table, base)
+}
+
+#[test]
+fn twolevel_unsized_hashtable() {
+    let tracker = Tracker::default();
+    let (mut table, mut base) = build(&tracker, 0);
+    assert_eq!(tracker.allocated(), 0);
+    unsafe {
+        table.insert(b"a").ok().unwrap().write(1);
+    }
+    *base.entry(b"a".to_vec()).or_default() += 1;
+    let (other, other_base) = build(&tracker, 100000);
+    for (key, count) in other_base {
+        *base.entry(key).or_default() += count;
+    }
+    unsafe {
+        table.merge(other, |_, result, count| match result {
+            Ok(x) => {
+                x.write(count);
+            }
+            Err(x) => {
+                *x += count;
+            }
+        });
+    }
+    assert_eq!(table.len(), base.len());
+    for (key, count) in base.iter() {
+        assert_eq!(table.get(key), Some(count));
+        let bucket = (key_hash::<[u8]>(key) >> (64 - 8)) as usize;
+        // Keys ending with zero are stored as long keys, whatever their lengths.
+        if key.len() > 2 || key.last() == Some(&0) {
+            assert_eq!(table.bucket(bucket).get(key), Some(count));
+        } else {
+            assert_eq!(table.short_bucket().get(key), Some(count));
+        }
+    }
+    for (key, count) in table.iter() {
+        assert_eq!(base[key], *count);
+    }
+    let entries = table.into_iter().collect::<HashMap<_, _>>();
+    assert_eq!(entries.len(), base.len());
+    for (key, count) in entries {
+        assert_eq!(base[&key[..]], count);
+    }
+    assert_eq!(tracker.allocated(), 0);
+}
+
+#[test]
+fn twolevel_unsized_hashtable_merge_buckets() {
+    let tracker = Tracker::default();
+    let (table, mut base) = build(&tracker, 50000);
+    let (other, other_base) = build(&tracker, 50000);
+    for (key, count) in other_base {
+        *base.entry(key).or_default() += count;
+    }
+    let (mut short, mut buckets) = table.into_buckets();
+    let (other_short, other_buckets) = other.into_buckets();
+    let merge = |_: &[u8], result: Result<&mut MaybeUninit<u64>, &mut u64>, count| match result {
+        Ok(x) => {
+            x.write(count);
+        }
+        Err(x) => {
+            *x += count;
+        }
+    };
+    std::thread::scope(|scope| {
+        scope.spawn(|| unsafe { short.merge(other_short, merge) });
+        for (bucket, other_bucket) in buckets.iter_mut().zip(other_buckets) {
+            scope.spawn(move || unsafe { bucket.merge(other_bucket, merge) });
+        }
+    });
+    let table = unsafe { Table::from_buckets(short, buckets) };
+    assert_eq!(table.len(), base.len());
+    for (key, count) in base.iter() {
+        assert_eq!(table.get(key), Some(count));
+    }
+    drop(table);
+    assert_eq!(tracker.allocated(), 0);
+}
@@ -0,0 +1,77 @@
+use hashtable::unsized_hashtable::UnsizedHashtable;
+use rand::Rng;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+fn random_key() -> Vec<u8> {
+    let mut rng = rand::thread_rng();
+    let len = rng.gen_range(0..40);
+    let mut key = (0..len)
+        .map(|_| rng.gen_range(b'a'..b'c'))
+        .collect::<Vec<_>>();
+    if let Some(last) = key.last_mut() {
+        *last = rng.gen_range(0..8);
+    }
+    key
+}
+
+#[test]
+fn remove_and_entry() {
+    let marker = Rc::new(());
+    let mut table = UnsizedHashtable::<[u8], (u64, Rc<()>)>::new();
+    let mut base = HashMap::<Vec<u8>, u64>::new();
+    for _ in 0..100000 {
+        let key = random_key();
+        if rand::thread_rng().gen_bool(0.3) {
+            let removed = table.remove(&key).map(|x| x.0);
+            assert_eq!(removed, base.remove(&key));
+        } else {
+            table
+                .entry(&key)
+                .and_modify(|x| x.0 += 1)
+                .or_insert_with(|| (1, marker.clone()));
+            *base.entry(key).or_default() += 1;
+        }
+    }
+    assert_eq!(table.len(), base.len());
+    assert_eq!(Rc::strong_count(&marker), 1 + table.len());
+    for (key, count) in base.iter() {
+        assert_eq!(table.get(key).map(|x| x.0), Some(*count));
+    }
+    for (_, value) in table.iter_mut() {
+        value.0 *= 2;
+    }
+    table.retain(|key, value| key.len() % 2 == 0 && value.0 % 4 == 0);
+    base.retain(|key, value| key.len() % 2 == 0 && (*value * 2) % 4 == 0);
+    assert_eq!(table.len(), base.len());
+    assert_eq!(Rc::strong_count(&marker), 1 + table.len());
+    for (key, count) in base.iter() {
+        assert_eq!(table.get(key).map(|x| x.0), Some(*count * 2));
+    }
+    let mut len = table.len();
+    let mut iter = table.into_iter();
+    assert_eq!(iter.size_hint(), (len, Some(len)));
+    iter.next().unwrap();
+    len -= 1;
+    for (key, (count, _)) in iter.by_ref().take(len / 2) {
+        assert_eq!(base[&key[..]] * 2, count);
+    }
+    drop(iter);
+    assert_eq!(Rc::strong_count(&marker), 1);
+}
+
+#[test]
+fn into_iter_str() {
+    let mut table = UnsizedHashtable::<str, usize>::new();
+    let keys = (0..1000).map(|i| "y".repeat(i % 50)).collect::<Vec<_>>();
+    for key in keys.iter() {
+        *table.entry(key).or_default() += 1;
+    }
+    let mut pairs = table.into_iter().collect::<Vec<_>>();
+    pairs.sort();
+    assert_eq!(pairs.len(), 50);
+    for (i, (key, count)) in pairs.into_iter().enumerate() {
+        assert_eq!(&*key, "y".repeat(i));
+        assert_eq!(count, 20);
+    }
+}